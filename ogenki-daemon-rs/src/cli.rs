@@ -1,12 +1,109 @@
-use clap::Parser;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 
-#[derive(Parser, Debug)]
+use clap::{Parser, ValueEnum};
+use reqwest::header::{HeaderName, HeaderValue};
+
+#[derive(Parser, Debug, Clone)]
 pub struct Serial {
-    #[arg(required = true, env)]
-    pub serial_port: String,
+    #[arg(required_unless_present = "input_file", env)]
+    pub serial_port: Option<String>,
+
+    /// Read captured serial lines from a file instead of the serial port.
+    /// The daemon drains the send queue and exits at the end of the file.
+    #[arg(long, env, conflicts_with = "serial_port")]
+    pub input_file: Option<PathBuf>,
+
+    /// Delay between lines of --input-file.
+    #[arg(long, env, default_value_t = 0)]
+    pub replay_interval_ms: u64,
 
     #[arg(long, env, default_value_t = 115200)]
     pub baudrate: u32,
+
+    #[arg(long, env, default_value_t = 2)]
+    pub serial_retry_secs: u64,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum QueueDrop {
+    Oldest,
+    Newest,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum Output {
+    Human,
+    Json,
+    Openmetrics,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum MqttPayloadFormat {
+    Json,
+    #[value(name = "msgpack")]
+    MessagePack,
+    Cbor,
+    PlainText,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum PayloadFormat {
+    Multipart,
+    Json,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum UdpFormat {
+    Statsd,
+    Influx,
+}
+
+fn parse_tag(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .ok_or_else(|| format!("expected key=value, got {s}"))
+}
+
+fn parse_header(s: &str) -> Result<(HeaderName, HeaderValue), String> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected Name: Value, got {s}"))?;
+
+    let name = HeaderName::try_from(name.trim()).map_err(|e| format!("{name}: {e}"))?;
+    let value = HeaderValue::try_from(value.trim()).map_err(|e| format!("{value}: {e}"))?;
+
+    Ok((name, value))
+}
+
+/// An extra web backend given by `--backend`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebTarget {
+    pub url: reqwest::Url,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// `url`, `url;username` or `url;username;password`.
+/// The password is everything after the second `;`, so it may contain `;`.
+fn parse_web_target(s: &str) -> Result<WebTarget, String> {
+    let mut parts = s.splitn(3, ';');
+
+    let url = parts.next().unwrap_or_default();
+    let url = reqwest::Url::parse(url).map_err(|e| format!("{url}: {e}"))?;
+
+    let mut next = || parts.next().filter(|s| !s.is_empty()).map(String::from);
+    let (username, password) = (next(), next());
+
+    if password.is_some() && username.is_none() {
+        return Err(format!("{s}: password is given without username"));
+    }
+
+    Ok(WebTarget {
+        url,
+        username,
+        password,
+    })
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -17,8 +114,103 @@ pub struct Backend {
     #[arg(long, short, env)]
     pub password: Option<String>,
 
+    #[arg(long, env)]
+    pub token: Option<String>,
+
+    #[arg(long = "header", env, value_parser = parse_header)]
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+
+    #[arg(long, env)]
+    pub client_cert: Option<PathBuf>,
+
+    #[arg(long, env)]
+    pub client_key: Option<PathBuf>,
+
+    #[arg(long, env)]
+    pub ca_cert: Option<PathBuf>,
+
+    /// UNSAFE: disables server certificate verification. For lab use only.
+    #[arg(long, env)]
+    pub danger_accept_invalid_certs: bool,
+
     #[arg(env)]
     pub url: Option<reqwest::Url>,
+
+    /// URL as an option, for when there is no SERIAL_PORT before it, as with --input-file.
+    #[arg(long = "url", value_name = "URL", conflicts_with = "url")]
+    pub url_option: Option<reqwest::Url>,
+
+    /// Additional web backend as "url;username;password", repeatable.
+    /// Each gets its own credentials and spool subdirectory, backend1, backend2, ...
+    /// in the given order; every other web option is shared.
+    #[arg(long = "backend", env, value_parser = parse_web_target)]
+    pub web_targets: Vec<WebTarget>,
+
+    #[arg(long, env, value_enum, default_value_t = PayloadFormat::Multipart)]
+    pub payload_format: PayloadFormat,
+
+    #[arg(long, env, default_value_t = 3)]
+    pub max_retries: u32,
+
+    #[arg(long, env, default_value_t = 100)]
+    pub retry_base_delay_ms: u64,
+
+    #[arg(long, env, default_value_t = 0, allow_hyphen_values = true)]
+    pub battery_calibration_mv: i16,
+
+    #[arg(long, env)]
+    pub log_file: Option<PathBuf>,
+
+    #[arg(long, env)]
+    pub csv_file: Option<PathBuf>,
+
+    #[arg(long, env)]
+    pub backend_retry_queue_file: Option<PathBuf>,
+
+    #[arg(long, env, default_value_t = 10000)]
+    pub retry_queue_max_entries: usize,
+
+    #[arg(long, env)]
+    pub spool_dir: Option<PathBuf>,
+
+    #[arg(long, env, default_value_t = 1024 * 1024)]
+    pub spool_max_bytes: u64,
+
+    #[arg(long, env, default_value_t = 1)]
+    pub batch_size: usize,
+
+    #[arg(long, env, default_value_t = 1000)]
+    pub batch_interval_ms: u64,
+
+    #[arg(long, env)]
+    pub udp_addr: Option<String>,
+
+    #[arg(long, env, value_enum, default_value_t = UdpFormat::Statsd)]
+    pub udp_format: UdpFormat,
+
+    #[arg(long, env)]
+    pub statsd_tags: bool,
+
+    #[arg(long = "influx-tag", env, value_parser = parse_tag, value_delimiter = ',')]
+    pub influx_tags: Vec<(String, String)>,
+
+    #[arg(long, env)]
+    pub slack_webhook_url: Option<reqwest::Url>,
+
+    #[arg(long, env)]
+    pub opentsdb_addr: Option<String>,
+
+    #[arg(long, env, default_value = "twelite")]
+    pub opentsdb_prefix: String,
+
+    #[arg(long, env)]
+    pub mqtt_url: Option<reqwest::Url>,
+
+    #[arg(long, env, default_value = "ogenki/{hardware_id}")]
+    pub mqtt_topic: String,
+
+    #[arg(long, env, value_enum, default_value_t = MqttPayloadFormat::Json)]
+    pub mqtt_payload_format: MqttPayloadFormat,
 }
 
 #[derive(Parser, Debug)]
@@ -29,4 +221,286 @@ pub struct Cli {
 
     #[command(flatten)]
     pub backend: Backend,
+
+    /// Replace hardware ids with a pseudonym, the same for each device.
+    /// The mapping is public and reversible, so this is not a substitute for access control.
+    #[arg(long, env)]
+    pub anonymize_hardware_id: bool,
+
+    #[arg(long, env)]
+    pub verbose_decode_errors: bool,
+
+    #[arg(long, env)]
+    pub require_direct: bool,
+
+    #[arg(long, env, default_value_t = 1024)]
+    pub queue_capacity: usize,
+
+    #[arg(long, env, value_enum, default_value_t = QueueDrop::Oldest)]
+    pub queue_drop: QueueDrop,
+
+    #[arg(long, env, value_enum, default_value_t = Output::Human)]
+    pub output: Output,
+
+    /// Serves /metrics and /healthz.
+    #[arg(long, env)]
+    pub metrics_addr: Option<SocketAddr>,
+
+    /// Serves /healthz, if it should be on another address than --metrics-addr.
+    #[arg(long, env)]
+    pub health_addr: Option<SocketAddr>,
+
+    /// Devices silent for longer than this are reported as stale on /healthz.
+    #[arg(long, env, default_value_t = 300)]
+    pub stale_secs: u64,
+
+    #[arg(long, env)]
+    pub on_change_only: bool,
+
+    #[arg(long, env, default_value_t = 0)]
+    pub min_interval_secs: u64,
+
+    #[arg(long, env, default_value_t = 10)]
+    pub shutdown_timeout_secs: u64,
+
+    /// Warn every N seconds without a valid frame, such as when the
+    /// coordinator is stuck or unplugged.
+    #[arg(long, env)]
+    pub watchdog_secs: Option<u64>,
+
+    /// Exit with status 1 when the watchdog fires, so a supervisor restarts the daemon.
+    #[arg(long, env, requires = "watchdog_secs")]
+    pub watchdog_exit: bool,
+}
+
+impl Serial {
+    fn validate(&self, errors: &mut Vec<String>) {
+        if !(1200..=1_000_000).contains(&self.baudrate) {
+            errors.push(format!(
+                "baudrate {} is out of range (1200..=1000000)",
+                self.baudrate
+            ));
+        }
+
+        // Only on Unix, as ports such as COM3 on Windows are not paths.
+        #[cfg(unix)]
+        if let Some(port) = &self.serial_port {
+            if !std::path::Path::new(port).exists() {
+                errors.push(format!("serial port {port} does not exist"));
+            }
+        }
+    }
+}
+
+impl Backend {
+    fn validate(&self, errors: &mut Vec<String>) {
+        for url in self
+            .url
+            .iter()
+            .chain(self.web_targets.iter().map(|t| &t.url))
+        {
+            if !matches!(url.scheme(), "http" | "https") {
+                errors.push(format!("url {url} must be http or https"));
+            }
+        }
+
+        if let Some(url) = &self.mqtt_url {
+            if !matches!(url.scheme(), "mqtt" | "tcp") {
+                errors.push(format!("mqtt-url {url} must be mqtt or tcp"));
+            }
+
+            if url.host_str().is_none() {
+                errors.push(format!("mqtt-url {url} has no host"));
+            }
+        }
+
+        if self.password.is_some() && self.username.is_none() {
+            errors.push("password is given without username".to_string());
+        }
+
+        if self.client_cert.is_some() != self.client_key.is_some() {
+            errors.push("client-cert and client-key must be given together".to_string());
+        }
+
+        if self.token.is_some() && self.username.is_some() {
+            errors.push("token and username are mutually exclusive".to_string());
+        }
+
+        if self.backend_retry_queue_file.is_some() && self.retry_queue_max_entries == 0 {
+            errors.push("retry-queue-max-entries must be at least 1".to_string());
+        }
+
+        if self.spool_dir.is_some() && self.spool_max_bytes < 24 {
+            errors.push("spool-max-bytes must hold at least one frame (24 bytes)".to_string());
+        }
+    }
+}
+
+impl Cli {
+    /// Take --url as URL, so the rest of the daemon only looks at `backend.url`.
+    pub fn resolve_url(mut self) -> Self {
+        if let Some(url) = self.backend.url_option.take() {
+            self.backend.url = Some(url);
+        }
+
+        self
+    }
+
+    /// Check every argument at once, so that all mistakes are reported in a
+    /// single run instead of failing on the first one at startup.
+    pub fn validate_all(&self) -> Result<(), Vec<String>> {
+        let mut errors = vec![];
+
+        self.serial.validate(&mut errors);
+        self.backend.validate(&mut errors);
+
+        if self.queue_capacity == 0 {
+            errors.push("queue-capacity must be at least 1".to_string());
+        }
+
+        if self.watchdog_secs == Some(0) {
+            errors.push("watchdog-secs must be at least 1".to_string());
+        }
+
+        if self.stale_secs == 0 {
+            errors.push("stale-secs must be at least 1".to_string());
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+#[test]
+fn test_validate_all() {
+    let cli = Cli::try_parse_from(["ogenki-daemon-rs", "/"]).unwrap();
+    assert_eq!(cli.validate_all(), Ok(()));
+
+    let cli = Cli::try_parse_from([
+        "ogenki-daemon-rs",
+        "/",
+        "ftp://example.com/",
+        "--baudrate",
+        "0",
+        "--password",
+        "secret",
+        "--queue-capacity",
+        "0",
+        "--watchdog-secs",
+        "0",
+        "--stale-secs",
+        "0",
+    ])
+    .unwrap();
+
+    assert_eq!(
+        cli.validate_all(),
+        Err(vec![
+            "baudrate 0 is out of range (1200..=1000000)".to_string(),
+            "url ftp://example.com/ must be http or https".to_string(),
+            "password is given without username".to_string(),
+            "queue-capacity must be at least 1".to_string(),
+            "watchdog-secs must be at least 1".to_string(),
+            "stale-secs must be at least 1".to_string(),
+        ]),
+    );
+
+    #[cfg(unix)]
+    {
+        let cli = Cli::try_parse_from(["ogenki-daemon-rs", "/dev/ogenki-missing"]).unwrap();
+
+        assert_eq!(
+            cli.validate_all(),
+            Err(vec![
+                "serial port /dev/ogenki-missing does not exist".to_string()
+            ]),
+        );
+    }
+
+    let cli =
+        Cli::try_parse_from(["ogenki-daemon-rs", "/", "--token", "t", "--username", "u"]).unwrap();
+
+    assert_eq!(
+        cli.validate_all(),
+        Err(vec!["token and username are mutually exclusive".to_string()]),
+    );
+
+    assert!(Cli::try_parse_from(["ogenki-daemon-rs", "/", "--watchdog-exit"]).is_err());
+}
+
+#[test]
+fn test_input_file() {
+    let cli = Cli::try_parse_from(["ogenki-daemon-rs", "--input-file", "captured.log"]).unwrap();
+    assert_eq!(cli.serial.serial_port, None);
+    assert_eq!(cli.serial.input_file, Some(PathBuf::from("captured.log")));
+
+    assert!(
+        Cli::try_parse_from(["ogenki-daemon-rs", "/", "--input-file", "captured.log"]).is_err()
+    );
+    assert!(Cli::try_parse_from(["ogenki-daemon-rs"]).is_err());
+
+    // Without SERIAL_PORT, the first positional would be taken as one, so URL is given by --url
+    let cli = Cli::try_parse_from([
+        "ogenki-daemon-rs",
+        "--input-file",
+        "captured.log",
+        "--url",
+        "http://127.0.0.1:9/",
+    ])
+    .unwrap()
+    .resolve_url();
+    assert_eq!(cli.serial.serial_port, None);
+    assert_eq!(cli.backend.url.unwrap().as_str(), "http://127.0.0.1:9/");
+
+    assert!(Cli::try_parse_from([
+        "ogenki-daemon-rs",
+        "/dev/ttyUSB0",
+        "http://127.0.0.1:9/",
+        "--url",
+        "http://127.0.0.1:9/",
+    ])
+    .is_err());
+}
+
+#[test]
+fn test_parse_header() {
+    let (name, value) = parse_header("X-Api-Key: abc:def").unwrap();
+    assert_eq!(name, "x-api-key");
+    assert_eq!(value, "abc:def");
+
+    assert!(parse_header("X-Api-Key").is_err());
+    assert!(parse_header("X Api Key: abc").is_err());
+    assert!(parse_header("X-Tenant: a\nb").is_err());
+}
+
+#[test]
+fn test_parse_web_target() {
+    let backend = Backend::parse_from([
+        "test",
+        "--backend",
+        "https://primary.example.com/;alice;p;w",
+        "--backend",
+        "https://backup.example.com/",
+    ]);
+
+    assert_eq!(
+        backend.web_targets,
+        [
+            WebTarget {
+                url: "https://primary.example.com/".parse().unwrap(),
+                username: Some("alice".to_string()),
+                password: Some("p;w".to_string()),
+            },
+            WebTarget {
+                url: "https://backup.example.com/".parse().unwrap(),
+                username: None,
+                password: None,
+            },
+        ],
+    );
+
+    assert!(parse_web_target("not a url").is_err());
+    assert!(parse_web_target("https://example.com/;;secret").is_err());
 }
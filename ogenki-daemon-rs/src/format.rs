@@ -1,16 +1,409 @@
+use std::fmt::Write;
+use std::time::SystemTime;
+
 use twelite_serial::*;
 
+use crate::cli::MqttPayloadFormat;
+
+/// UTC, like `2024-05-01T12:34:56.789Z`.
+pub fn iso8601(time: SystemTime) -> String {
+    let since_epoch = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let secs = since_epoch.as_secs();
+    let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        since_epoch.subsec_millis(),
+    )
+}
+
+pub const CSV_HEADER: [&str; 13] = [
+    "timestamp",
+    "hardware_id",
+    "lqi",
+    "lqi_dbm",
+    "battery_mv",
+    "di1",
+    "di2",
+    "di3",
+    "di4",
+    "ad1_mv",
+    "ad2_mv",
+    "ad3_mv",
+    "ad4_mv",
+];
+
 pub trait FormatExt {
     fn format(&self) -> String;
+
+    /// The serde fields plus `received_at` in ISO-8601, as one line of JSON.
+    fn to_json_line(&self, received_at: SystemTime) -> String;
+
+    /// The serde fields for JSON and binary formats, [`FormatExt::format`] for plain text.
+    fn to_mqtt_payload(&self, format: MqttPayloadFormat) -> Vec<u8>;
+
+    /// One record matching [`CSV_HEADER`], for a [`csv::Writer`].
+    fn to_csv_row(&self, received_at: SystemTime) -> Vec<String>;
+
+    /// statsd gauge lines. DogStatsD tags are appended if `tags` is set.
+    fn to_statsd_lines(&self, tags: bool) -> String;
+
+    /// InfluxDB line protocol, with `extra_tags` after the device identity tags.
+    /// Ends with a newline; the timestamp is left to the server.
+    fn to_line_protocol_with_tags(&self, extra_tags: &[(&str, &str)]) -> String;
+
+    /// OpenTSDB `put` commands, one per metric. `unix_secs` is the receive time.
+    fn to_opentsdb_put_lines(&self, prefix: &str, unix_secs: u64) -> String;
+
+    /// A complete OpenMetrics exposition of this frame, ending with `# EOF`.
+    /// The battery voltage is in the base unit, volts.
+    fn to_openmetrics_text(&self) -> String;
 }
 
 impl FormatExt for StatusNotify {
     fn format(&self) -> String {
-        let dbm = self.lqi_dbm();
-        let mv = self.power_voltage_millis();
-        let open = self.di1_status();
-        let changed = self.di1_changed();
+        let ad = (1..=4)
+            .zip(StatusNotify::ad_channel_names())
+            .map(|(ch, name)| match self.ad_voltage_at_channel(ch) {
+                Some(mv) if self.ad_is_saturated(ch.into()) => format!("{name} {mv}mV saturated"),
+                Some(mv) => format!("{name} {mv}mV"),
+                None => format!("{name} invalid"),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{self} ad: [{ad}]")
+    }
+
+    fn to_json_line(&self, received_at: SystemTime) -> String {
+        let mut value = serde_json::to_value(self).unwrap();
+
+        value.as_object_mut().unwrap().insert(
+            "received_at".to_string(),
+            serde_json::Value::String(iso8601(received_at)),
+        );
+
+        value.to_string()
+    }
+
+    fn to_mqtt_payload(&self, format: MqttPayloadFormat) -> Vec<u8> {
+        match format {
+            MqttPayloadFormat::Json => serde_json::to_vec(self).unwrap(),
+            MqttPayloadFormat::MessagePack => rmp_serde::to_vec_named(self).unwrap(),
+            MqttPayloadFormat::Cbor => {
+                let mut buf = vec![];
+                ciborium::into_writer(self, &mut buf).unwrap();
+                buf
+            }
+            MqttPayloadFormat::PlainText => self.format().into_bytes(),
+        }
+    }
+
+    fn to_csv_row(&self, received_at: SystemTime) -> Vec<String> {
+        let mut row = vec![
+            iso8601(received_at),
+            format!("{:08X}", self.hardware_id()),
+            self.lqi().to_string(),
+            self.lqi_dbm().to_string(),
+            self.power_voltage_millis().to_string(),
+        ];
+
+        row.extend(self.di_statuses().map(|di| (di as u8).to_string()));
+        row.extend(self.ad_voltages_millis().map(|mv| mv.to_string()));
+
+        row
+    }
+
+    fn to_statsd_lines(&self, tags: bool) -> String {
+        let tags = match tags {
+            true => format!(
+                "|#device:0x{:02X},hardware:0x{:08X}",
+                self.source_device_id(),
+                self.hardware_id(),
+            ),
+            false => String::new(),
+        };
+
+        [
+            ("lqi", self.lqi().to_string()),
+            ("lqi_dbm", self.lqi_dbm().to_string()),
+            ("battery_mv", self.power_voltage_millis().to_string()),
+            ("di_status", self.di_status().to_string()),
+        ]
+        .iter()
+        .map(|(name, value)| format!("twelite.{name}:{value}|g{tags}\n"))
+        .collect()
+    }
+
+    fn to_line_protocol_with_tags(&self, extra_tags: &[(&str, &str)]) -> String {
+        let escape = |s: &str| {
+            s.replace('\\', "\\\\")
+                .replace(',', "\\,")
+                .replace('=', "\\=")
+                .replace(' ', "\\ ")
+        };
+
+        let mut line = format!(
+            "twelite,source_device_id={},hardware_id={:08X}",
+            self.source_device_id(),
+            self.hardware_id(),
+        );
+
+        for (k, v) in extra_tags {
+            line += &format!(",{}={}", escape(k), escape(v));
+        }
+
+        line += &format!(
+            " lqi={}i,lqi_dbm={},battery_mv={}i,di_status={}i\n",
+            self.lqi(),
+            self.lqi_dbm(),
+            self.power_voltage_millis(),
+            self.di_status(),
+        );
+
+        line
+    }
+
+    fn to_opentsdb_put_lines(&self, prefix: &str, unix_secs: u64) -> String {
+        let tags = format!(
+            "source_device_id={} hardware_id={:08X}",
+            self.source_device_id(),
+            self.hardware_id(),
+        );
+
+        [
+            ("lqi", self.lqi().to_string()),
+            ("lqi_dbm", self.lqi_dbm().to_string()),
+            ("battery_mv", self.power_voltage_millis().to_string()),
+            ("di_status", self.di_status().to_string()),
+        ]
+        .iter()
+        .map(|(name, value)| format!("put {prefix}.{name} {unix_secs} {value} {tags}\n"))
+        .collect()
+    }
+
+    fn to_openmetrics_text(&self) -> String {
+        let labels = format!(
+            "source_device_id=\"{}\",hardware_id=\"{:08X}\"",
+            self.source_device_id(),
+            self.hardware_id(),
+        );
 
-        format!("{dbm:.2}dBm {mv}mV is_open: {open} changed: {changed}")
+        let mut s = String::new();
+
+        for (name, unit, help, value) in [
+            (
+                "twelite_battery_volts",
+                Some("volts"),
+                "Supply voltage.",
+                (self.power_voltage_millis() as f64 / 1000.0).to_string(),
+            ),
+            (
+                "twelite_lqi",
+                None,
+                "Link quality indicator, 0 to 255.",
+                self.lqi().to_string(),
+            ),
+            (
+                "twelite_lqi_dbm",
+                Some("dbm"),
+                "Received signal strength.",
+                self.lqi_dbm().to_string(),
+            ),
+        ] {
+            writeln!(s, "# TYPE {name} gauge").unwrap();
+
+            if let Some(unit) = unit {
+                writeln!(s, "# UNIT {name} {unit}").unwrap();
+            }
+
+            writeln!(s, "# HELP {name} {help}").unwrap();
+            writeln!(s, "{name}{{{labels}}} {value}").unwrap();
+        }
+
+        writeln!(s, "# TYPE twelite_di_open gauge").unwrap();
+        writeln!(
+            s,
+            "# HELP twelite_di_open Whether the digital input is open."
+        )
+        .unwrap();
+
+        for (n, open) in self.di_statuses().iter().enumerate() {
+            writeln!(
+                s,
+                "twelite_di_open{{{labels},channel=\"{}\"}} {}",
+                n + 1,
+                *open as u8,
+            )
+            .unwrap();
+        }
+
+        s.push_str("# EOF\n");
+        s
     }
 }
+
+#[test]
+fn test_to_statsd_lines() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        notify.to_statsd_lines(false),
+        "twelite.lqi:117|g\n\
+         twelite.lqi_dbm:-57.55|g\n\
+         twelite.battery_mv:3076|g\n\
+         twelite.di_status:0|g\n",
+    );
+
+    assert_eq!(
+        notify.to_statsd_lines(true).lines().next(),
+        Some("twelite.lqi:117|g|#device:0x78,hardware:0x81000038"),
+    );
+}
+
+#[test]
+fn test_format() {
+    // AD1 = 0x10, AD2 = 0x7C, AD3 = 0xFF, AD4 = 0xFF, fix = 0
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFF7C100018").unwrap();
+
+    assert_eq!(
+        notify.format(),
+        "-57.55dBm 3076mV is_open: false changed: false \
+         ad: [AI1 256mV, AI2 1984mV saturated, AI3 invalid, AI4 invalid]",
+    );
+}
+
+#[test]
+fn test_to_opentsdb_put_lines() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        notify
+            .to_opentsdb_put_lines("twelite", 1700000000)
+            .lines()
+            .next(),
+        Some("put twelite.lqi 1700000000 117 source_device_id=120 hardware_id=81000038"),
+    );
+    assert_eq!(
+        notify.to_opentsdb_put_lines("twelite", 0).lines().count(),
+        4
+    );
+}
+
+#[test]
+fn test_iso8601() {
+    use std::time::Duration;
+
+    assert_eq!(iso8601(SystemTime::UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    assert_eq!(
+        iso8601(SystemTime::UNIX_EPOCH + Duration::from_millis(1709210096789)),
+        "2024-02-29T12:34:56.789Z",
+    );
+}
+
+#[test]
+fn test_to_json_line() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let value: serde_json::Value =
+        serde_json::from_str(&notify.to_json_line(SystemTime::UNIX_EPOCH)).unwrap();
+
+    assert_eq!(value["received_at"], "1970-01-01T00:00:00.000Z");
+    assert_eq!(value["hardware_id"], 0x81000038u32);
+    assert_eq!(value["power_voltage_millis"], 3076);
+}
+
+#[test]
+fn test_to_mqtt_payload() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // Every serde format reads back to the same frame
+    let json = notify.to_mqtt_payload(MqttPayloadFormat::Json);
+    let decoded: StatusNotify = serde_json::from_slice(&json).unwrap();
+    assert_eq!(decoded, notify);
+
+    let value = serde_json::to_value(notify).unwrap();
+
+    assert_eq!(
+        notify.to_mqtt_payload(MqttPayloadFormat::PlainText),
+        notify.format().into_bytes(),
+    );
+
+    let msgpack = notify.to_mqtt_payload(MqttPayloadFormat::MessagePack);
+    let decoded: StatusNotify = rmp_serde::from_slice(&msgpack).unwrap();
+    assert_eq!(decoded, notify);
+    let decoded: serde_json::Value = rmp_serde::from_slice(&msgpack).unwrap();
+    assert_eq!(decoded, value);
+
+    // "lqi": 117 as a positive fixint
+    assert!(msgpack.windows(5).any(|w| w == b"\xA3lqi\x75"));
+
+    let cbor = notify.to_mqtt_payload(MqttPayloadFormat::Cbor);
+    let decoded: StatusNotify = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(decoded, notify);
+    let decoded: serde_json::Value = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(decoded, value);
+
+    // "lqi": 117 as a one byte unsigned integer
+    assert!(cbor.windows(6).any(|w| w == b"\x63lqi\x18\x75"));
+}
+
+#[test]
+fn test_to_line_protocol_with_tags() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        notify.to_line_protocol_with_tags(&[]),
+        "twelite,source_device_id=120,hardware_id=81000038 \
+         lqi=117i,lqi_dbm=-57.55,battery_mv=3076i,di_status=0i\n",
+    );
+
+    assert!(notify
+        .to_line_protocol_with_tags(&[("location", "living room"), ("floor", "1")])
+        .starts_with(
+            "twelite,source_device_id=120,hardware_id=81000038,location=living\\ room,floor=1 "
+        ));
+}
+
+#[test]
+fn test_to_openmetrics_text() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    let text = notify.to_openmetrics_text();
+    let labels = "source_device_id=\"120\",hardware_id=\"81000038\"";
+
+    assert!(text.starts_with(
+        "# TYPE twelite_battery_volts gauge\n\
+         # UNIT twelite_battery_volts volts\n\
+         # HELP twelite_battery_volts Supply voltage.\n"
+    ));
+    assert!(text.contains(&format!("twelite_battery_volts{{{labels}}} 3.076\n")));
+    assert!(text.contains(&format!("twelite_lqi{{{labels}}} 117\n")));
+    assert!(text.contains("# UNIT twelite_lqi_dbm dbm\n"));
+    assert!(text.contains(&format!("twelite_di_open{{{labels},channel=\"1\"}} 1\n")));
+    assert!(text.ends_with("channel=\"4\"} 0\n# EOF\n"));
+}
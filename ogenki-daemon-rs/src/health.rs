@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use axum::Router;
+use serde_json::json;
+use twelite_serial::StatusNotify;
+
+struct DeviceState {
+    last_seen: Instant,
+    battery_mv: u16,
+    lqi: u8,
+}
+
+/// Last frame of each device, served as JSON on `/healthz`.
+pub struct Health {
+    devices: Mutex<BTreeMap<u32, DeviceState>>,
+    stale_after: Duration,
+}
+
+impl Health {
+    pub fn new(stale_after: Duration) -> Self {
+        Self {
+            devices: Mutex::new(BTreeMap::new()),
+            stale_after,
+        }
+    }
+
+    pub fn update(&self, notify: &StatusNotify, now: Instant) {
+        self.devices.lock().unwrap().insert(
+            notify.hardware_id(),
+            DeviceState {
+                last_seen: now,
+                battery_mv: notify.power_voltage_millis(),
+                lqi: notify.lqi(),
+            },
+        );
+    }
+
+    /// A device is stale if nothing was heard from it for longer than `--stale-secs`.
+    pub fn render(&self, now: Instant) -> String {
+        let devices = self.devices.lock().unwrap();
+
+        let devices: Vec<_> = devices
+            .iter()
+            .map(|(hardware_id, state)| {
+                let silent = now.saturating_duration_since(state.last_seen);
+
+                json!({
+                    "hardware_id": format!("{hardware_id:08X}"),
+                    "last_seen_secs": silent.as_secs(),
+                    "battery_mv": state.battery_mv,
+                    "lqi": state.lqi,
+                    "stale": silent > self.stale_after,
+                })
+            })
+            .collect();
+
+        json!({ "devices": devices }).to_string()
+    }
+
+    /// `/healthz`, for [`crate::server::serve`].
+    pub fn router(&'static self) -> Router {
+        Router::new().route(
+            "/healthz",
+            get(move || async move {
+                (
+                    [(CONTENT_TYPE, "application/json")],
+                    self.render(Instant::now()),
+                )
+            }),
+        )
+    }
+}
+
+#[test]
+fn test_render() {
+    let health = Health::new(Duration::from_secs(60));
+    let start = Instant::now();
+
+    health.update(
+        &StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap(),
+        start,
+    );
+
+    let rendered: serde_json::Value =
+        serde_json::from_str(&health.render(start + Duration::from_secs(30))).unwrap();
+
+    assert_eq!(
+        rendered,
+        json!({
+            "devices": [{
+                "hardware_id": "81000038",
+                "last_seen_secs": 30,
+                "battery_mv": 3076,
+                "lqi": 117,
+                "stale": false,
+            }],
+        }),
+    );
+
+    let rendered: serde_json::Value =
+        serde_json::from_str(&health.render(start + Duration::from_secs(61))).unwrap();
+
+    assert_eq!(rendered["devices"][0]["stale"], true);
+}
@@ -1,54 +1,382 @@
 mod cli;
 mod format;
+mod health;
+mod metrics;
+mod rate_limit;
+mod retry_queue;
+mod send_queue;
 mod sender;
+mod server;
+mod shutdown;
+mod spool;
 
-use std::io::{BufRead, BufReader};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
 use clap::Parser;
-use serialport::{DataBits, FlowControl, Parity, StopBits};
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+use tokio_serial::{DataBits, FlowControl, Parity, SerialPortBuilderExt, SerialStream, StopBits};
 
-use cli::Cli;
+use cli::{Cli, Output};
 use format::*;
+use health::*;
+use metrics::*;
+use rate_limit::*;
+use send_queue::*;
 use sender::*;
 use twelite_serial::*;
 
-#[tokio::main(flavor = "current_thread")]
+/// Lines read ahead of the decode pipeline.
+const LINE_CHANNEL_CAPACITY: usize = 64;
+
+/// Open the serial port, retrying every `--serial-retry-secs` until it succeeds.
+/// `None` without `--serial-port`.
+async fn open_serial(cli: &cli::Serial) -> Option<SerialStream> {
+    let serial_port = cli.serial_port.as_deref()?;
+
+    loop {
+        let serial = tokio_serial::new(serial_port, cli.baudrate)
+            .flow_control(FlowControl::None)
+            .data_bits(DataBits::Eight)
+            .parity(Parity::None)
+            .stop_bits(StopBits::One)
+            .open_native_async();
+
+        match serial {
+            Ok(serial) => return Some(serial),
+            Err(e) => {
+                eprintln!("Failed to open {serial_port}: {e}");
+                tokio::time::sleep(Duration::from_secs(cli.serial_retry_secs)).await;
+            }
+        }
+    }
+}
+
+/// Errors after which the port has to be reopened, such as an unplugged device.
+/// Undecodable lines are not.
+fn is_fatal(e: &std::io::Error) -> bool {
+    !matches!(e.kind(), ErrorKind::Interrupted | ErrorKind::InvalidData)
+}
+
+/// Send lines from the serial port to `lines`, reopening it after
+/// `--serial-retry-secs` on errors and EOF, until `lines` is closed.
+async fn read_serial(cli: cli::Serial, lines: mpsc::Sender<String>) {
+    'serial: while let Some(serial) = open_serial(&cli).await {
+        let mut reader = BufReader::new(serial).lines();
+
+        loop {
+            match reader.next_line().await {
+                Ok(Some(line)) => {
+                    if lines.send(line).await.is_err() {
+                        break 'serial;
+                    }
+                }
+                Ok(None) => {
+                    eprintln!("Serial port closed, reopening");
+                    break;
+                }
+                Err(e) if is_fatal(&e) => {
+                    eprintln!("Serial port error: {e}, reopening");
+                    break;
+                }
+                Err(_) => continue,
+            }
+        }
+
+        // A port that reopens fine but closes again at once would otherwise spin.
+        tokio::time::sleep(Duration::from_secs(cli.serial_retry_secs)).await;
+    }
+}
+
+/// Send lines of `--input-file` to `lines`, `interval` apart. `lines` is
+/// closed at the end of the file, which shuts the daemon down.
+async fn read_file(path: PathBuf, file: File, interval: Duration, lines: mpsc::Sender<String>) {
+    let mut reader = BufReader::new(file).lines();
+
+    loop {
+        match reader.next_line().await {
+            Ok(Some(line)) => {
+                if lines.send(line).await.is_err() {
+                    return;
+                }
+            }
+            Ok(None) => break,
+            Err(e) => {
+                eprintln!("Failed to read {}: {e}", path.display());
+                return;
+            }
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+
+    eprintln!("Reached end of {}", path.display());
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 1)]
 async fn main() {
-    let cli = Cli::parse();
+    let cli = Cli::parse().resolve_url();
+
+    if let Err(errors) = cli.validate_all() {
+        errors.iter().for_each(|e| eprintln!("error: {e}"));
+        std::process::exit(2);
+    }
+
     let sender: &'static Sender = Box::leak(Box::new(Sender::new(&cli.backend)));
 
-    let serial = serialport::new(&cli.serial.serial_port, cli.serial.baudrate)
-        .flow_control(FlowControl::None)
-        .data_bits(DataBits::Eight)
-        .parity(Parity::None)
-        .stop_bits(StopBits::One)
-        .timeout(std::time::Duration::from_secs(10))
-        .open()
-        .expect("Failed to open serial port");
+    sender.replay().await.expect("Failed to replay retry queue");
 
-    let serial = BufReader::new(serial);
+    let metrics: Option<&'static Metrics> = cli
+        .metrics_addr
+        .map(|_| &*Box::leak(Box::new(Metrics::new())));
 
-    for line in serial.lines() {
-        let Ok(line) = line else {
-            continue;
-        };
+    let health: Option<&'static Health> = cli
+        .metrics_addr
+        .or(cli.health_addr)
+        .map(|_| &*Box::leak(Box::new(Health::new(Duration::from_secs(cli.stale_secs)))));
+
+    // /healthz is always next to /metrics, and on --health-addr too if that differs.
+    let servers = match (cli.metrics_addr, cli.health_addr) {
+        (Some(metrics_addr), Some(health_addr)) if metrics_addr != health_addr => {
+            vec![(metrics_addr, metrics, health), (health_addr, None, health)]
+        }
+        (Some(addr), _) | (None, Some(addr)) => vec![(addr, metrics, health)],
+        (None, None) => vec![],
+    };
+
+    for (addr, metrics, health) in servers {
+        server::serve(addr, metrics, health)
+            .await
+            .expect("Failed to start HTTP server");
+    }
+
+    let send_queue: &'static SendQueue =
+        Box::leak(Box::new(SendQueue::new(cli.queue_capacity, cli.queue_drop)));
+
+    tokio::spawn(async move {
+        loop {
+            let status = send_queue.pop().await;
+
+            if let Err(e) = sender.send(&status).await {
+                eprintln!("Failed to send: {e:#}");
+
+                if let Some(metrics) = metrics {
+                    metrics.inc_failed_sends();
+                }
+            }
+
+            send_queue.done();
+        }
+    });
+
+    if cli.backend.batch_size > 1 {
+        let period = Duration::from_millis(cli.backend.batch_interval_ms.max(1));
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = sender.flush().await {
+                    eprintln!("Failed to flush batch: {e:#}");
+
+                    if let Some(metrics) = metrics {
+                        metrics.inc_failed_sends();
+                    }
+                }
+            }
+        });
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            match send_queue.take_dropped() {
+                0 => {}
+                n => eprintln!("Dropped {n} frames in the last minute: send queue is full"),
+            }
+        }
+    });
+
+    let mut signals = shutdown::Signals::new().expect("Failed to install signal handler");
 
-        let status = match StatusNotify::decode_str(&line) {
+    let mut last_di_status: HashMap<u32, u8> = HashMap::new();
+    let mut rate_limiter = RateLimiter::new(Duration::from_secs(cli.min_interval_secs));
+
+    // Decode, print, count and enqueue one line from the serial port or --input-file.
+    // `true` if it was a valid frame, even if it was not sent.
+    let mut handle_line = |line: &str| -> bool {
+        let status = match StatusNotify::decode_validated(line) {
             Ok(v) => v,
-            Err(e) => {
+            // Banners and other message types are not status frames.
+            Err(TweliteError::Decode(_))
+                if !cli.verbose_decode_errors
+                    && (!line.starts_with(':') || line.trim_end().len() != PACKET_ASCII_LENGTH) =>
+            {
+                return false;
+            }
+            Err(e @ TweliteError::Decode(_)) => {
                 eprintln!("{e}");
                 eprintln!("Buffer: {line}");
-                continue;
+                return false;
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                return false;
             }
         };
 
-        if let Err(v) = status.validate() {
-            eprintln!("{v}");
-            continue;
+        if cli.require_direct {
+            if let Err(relay_count) = status.validate_no_relay() {
+                eprintln!(
+                    "Relayed frame from {:08X} rejected: relay count {relay_count}",
+                    status.hardware_id()
+                );
+                return false;
+            }
+        }
+
+        let status = match cli.anonymize_hardware_id {
+            true => status.mask_sensitive_fields(),
+            false => status,
+        };
+
+        // Calibrated once here, so every output and backend agrees.
+        let status = status.with_power_voltage_calibrated(cli.backend.battery_calibration_mv);
+
+        match cli.output {
+            Output::Human => println!("{}", status.format()),
+            Output::Json => {
+                let mut stdout = std::io::stdout().lock();
+                writeln!(stdout, "{}", status.to_json_line(SystemTime::now())).unwrap();
+                stdout.flush().unwrap();
+            }
+            Output::Openmetrics => {
+                let mut stdout = std::io::stdout().lock();
+                write!(stdout, "{}", status.to_openmetrics_text()).unwrap();
+                stdout.flush().unwrap();
+            }
+        }
+
+        if let Some(metrics) = metrics {
+            metrics.update(&status);
+        }
+
+        if let Some(health) = health {
+            health.update(&status, Instant::now());
+        }
+
+        // Every frame is still validated, printed and counted in metrics;
+        // --on-change-only and --min-interval-secs only skip sending.
+        // When combined, a change dropped by the interval is not recorded,
+        // so the next frame after the interval still carries it.
+        if cli.on_change_only
+            && last_di_status.get(&status.hardware_id()) == Some(&status.di_status())
+        {
+            return true;
+        }
+
+        if !rate_limiter.allow(status.hardware_id(), Instant::now()) {
+            return true;
+        }
+
+        last_di_status.insert(status.hardware_id(), status.di_status());
+
+        if !send_queue.push(status) {
+            if let Some(metrics) = metrics {
+                metrics.inc_dropped_sends();
+            }
+        }
+
+        true
+    };
+
+    let (tx, mut lines) = mpsc::channel(LINE_CHANNEL_CAPACITY);
+
+    match cli.serial.input_file.clone() {
+        Some(path) => {
+            let file = File::open(&path).await.expect("Failed to open input file");
+            let interval = Duration::from_millis(cli.serial.replay_interval_ms);
+
+            tokio::spawn(read_file(path, file, interval, tx));
+        }
+        None => {
+            tokio::spawn(read_serial(cli.serial.clone(), tx));
         }
+    }
+
+    let watchdog_timeout = Duration::from_secs(cli.watchdog_secs.unwrap_or_default());
+    let watchdog = tokio::time::sleep(watchdog_timeout);
+    tokio::pin!(watchdog);
+
+    let mut watchdog_expired = false;
+
+    loop {
+        tokio::select! {
+            line = lines.recv() => match line {
+                Some(line) => {
+                    if handle_line(&line) {
+                        watchdog.as_mut().reset(tokio::time::Instant::now() + watchdog_timeout);
+                    }
+                }
+                None => break,
+            },
+            _ = signals.recv() => break,
+            _ = &mut watchdog, if cli.watchdog_secs.is_some() => {
+                eprintln!("No valid frame for {} seconds", watchdog_timeout.as_secs());
+
+                if cli.watchdog_exit {
+                    watchdog_expired = true;
+                    break;
+                }
+
+                watchdog.as_mut().reset(tokio::time::Instant::now() + watchdog_timeout);
+            }
+        }
+    }
+
+    eprintln!("Shutting down, draining {} frames", send_queue.pending());
+
+    // The send queue first, then anything the backends still buffer.
+    let drain = async {
+        while send_queue.pending() > 0 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        if let Err(e) = sender.flush().await {
+            eprintln!("Failed to flush: {e:#}");
+        }
+    };
+
+    let timeout = Duration::from_secs(cli.shutdown_timeout_secs);
+
+    tokio::select! {
+        drained = tokio::time::timeout(timeout, drain) => {
+            if drained.is_err() {
+                eprintln!(
+                    "Shutdown timed out, {} frames were not sent",
+                    send_queue.pending()
+                );
+                std::process::exit(1);
+            }
+        }
+        _ = signals.recv() => {
+            eprintln!("Interrupted again, {} frames were not sent", send_queue.pending());
+            std::process::exit(1);
+        }
+    }
 
-        println!("{}", status.format());
+    eprintln!("Send queue drained, exiting");
 
-        tokio::spawn(async move { sender.send(&status).await.unwrap() });
+    if watchdog_expired {
+        std::process::exit(1);
     }
 }
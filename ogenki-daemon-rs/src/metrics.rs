@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use axum::Router;
+use twelite_serial::StatusNotify;
+
+/// Latest frame of each device, served as Prometheus text format on `/metrics`.
+pub struct Metrics {
+    latest: Mutex<BTreeMap<u32, StatusNotify>>,
+    dropped_sends: AtomicU64,
+    failed_sends: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            latest: Mutex::new(BTreeMap::new()),
+            dropped_sends: AtomicU64::new(0),
+            failed_sends: AtomicU64::new(0),
+        }
+    }
+
+    pub fn update(&self, notify: &StatusNotify) {
+        self.latest
+            .lock()
+            .unwrap()
+            .insert(notify.hardware_id(), *notify);
+    }
+
+    /// A frame was not sent because the send queue was full.
+    pub fn inc_dropped_sends(&self) {
+        self.dropped_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Sending a frame failed on at least one backend.
+    pub fn inc_failed_sends(&self) {
+        self.failed_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let latest = self.latest.lock().unwrap();
+        let mut s = String::new();
+
+        let mut gauge = |name: &str, value: fn(&StatusNotify) -> String| {
+            writeln!(s, "# TYPE {name} gauge").unwrap();
+
+            for (hardware_id, notify) in latest.iter() {
+                writeln!(
+                    s,
+                    "{name}{{hardware_id=\"{hardware_id:08X}\"}} {}",
+                    value(notify),
+                )
+                .unwrap();
+            }
+        };
+
+        gauge("ogenki_battery_millivolts", |n| {
+            n.power_voltage_millis().to_string()
+        });
+        gauge("ogenki_lqi", |n| n.lqi().to_string());
+        gauge("ogenki_lqi_dbm", |n| n.lqi_dbm().to_string());
+
+        writeln!(s, "# TYPE ogenki_di_open gauge").unwrap();
+
+        for (hardware_id, notify) in latest.iter() {
+            for (n, open) in notify.di_statuses().iter().enumerate() {
+                writeln!(
+                    s,
+                    "ogenki_di_open{{hardware_id=\"{hardware_id:08X}\",channel=\"{}\"}} {}",
+                    n + 1,
+                    *open as u8,
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(s, "# TYPE ogenki_dropped_sends_total counter").unwrap();
+        writeln!(
+            s,
+            "ogenki_dropped_sends_total {}",
+            self.dropped_sends.load(Ordering::Relaxed),
+        )
+        .unwrap();
+
+        writeln!(s, "# TYPE ogenki_failed_sends_total counter").unwrap();
+        writeln!(
+            s,
+            "ogenki_failed_sends_total {}",
+            self.failed_sends.load(Ordering::Relaxed),
+        )
+        .unwrap();
+
+        s
+    }
+
+    /// `/metrics`, for [`crate::server::serve`].
+    pub fn router(&'static self) -> Router {
+        Router::new().route(
+            "/metrics",
+            get(move || async move {
+                (
+                    [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+                    self.render(),
+                )
+            }),
+        )
+    }
+}
+
+#[test]
+fn test_render() {
+    let metrics = Metrics::new();
+
+    metrics.update(
+        &StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap(),
+    );
+
+    let rendered = metrics.render();
+
+    assert!(rendered.contains("ogenki_battery_millivolts{hardware_id=\"81000038\"} 3076\n"));
+    assert!(rendered.contains("ogenki_lqi{hardware_id=\"81000038\"} 117\n"));
+    assert!(rendered.contains("ogenki_lqi_dbm{hardware_id=\"81000038\"} -57.55\n"));
+    assert!(rendered.contains("ogenki_di_open{hardware_id=\"81000038\",channel=\"1\"} 1\n"));
+    assert!(rendered.contains("ogenki_di_open{hardware_id=\"81000038\",channel=\"2\"} 0\n"));
+    assert!(rendered.contains("ogenki_dropped_sends_total 0\n"));
+
+    metrics.inc_dropped_sends();
+    assert!(metrics.render().contains("ogenki_dropped_sends_total 1\n"));
+
+    metrics.inc_failed_sends();
+    assert!(metrics.render().contains("ogenki_failed_sends_total 1\n"));
+}
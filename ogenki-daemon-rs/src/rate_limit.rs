@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Minimum spacing between forwarded frames of each device.
+pub struct RateLimiter {
+    interval: Duration,
+    last_sent: HashMap<u32, Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// Returns `true`, and records `now`, if the last frame of `hardware_id`
+    /// was forwarded at least `interval` ago or never.
+    pub fn allow(&mut self, hardware_id: u32, now: Instant) -> bool {
+        match self.last_sent.get(&hardware_id) {
+            Some(last) if now.saturating_duration_since(*last) < self.interval => false,
+            _ => {
+                self.last_sent.insert(hardware_id, now);
+                true
+            }
+        }
+    }
+}
+
+#[test]
+fn test_rate_limiter() {
+    let mut limiter = RateLimiter::new(Duration::from_secs(10));
+    let t0 = Instant::now();
+
+    assert!(limiter.allow(0x81000038, t0));
+    assert!(!limiter.allow(0x81000038, t0 + Duration::from_secs(1)));
+    assert!(limiter.allow(0x81000039, t0 + Duration::from_secs(1)));
+    assert!(limiter.allow(0x81000038, t0 + Duration::from_secs(10)));
+    assert!(!limiter.allow(0x81000038, t0 + Duration::from_secs(19)));
+}
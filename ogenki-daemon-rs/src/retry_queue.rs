@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write as _};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use twelite_serial::StatusNotify;
+
+use crate::sender::Sender;
+
+/// Frames which failed to send, persisted as JSON Lines.
+/// Each line is an object with the backend that failed and the frame,
+/// like `{"backend":"url","frame":":7881...A7"}`, or a bare JSON string of
+/// the frame for every backend, as written by earlier versions.
+///
+/// The file is read once and mirrored in memory, so appending never re-reads it.
+/// File access runs on the blocking thread pool.
+pub struct RetryQueue {
+    path: PathBuf,
+    max_entries: usize,
+    lines: Mutex<Option<VecDeque<String>>>,
+}
+
+/// A queued frame and the backend to retry it on, or every backend if `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub backend: Option<String>,
+    pub notify: StatusNotify,
+}
+
+fn encode(notify: &StatusNotify) -> String {
+    notify
+        .as_bytes()
+        .iter()
+        .fold(String::from(":"), |mut s, b| {
+            write!(s, "{b:02X}").unwrap();
+            s
+        })
+}
+
+fn to_line(entry: &Entry) -> serde_json::Result<String> {
+    match &entry.backend {
+        Some(backend) => serde_json::to_string(&serde_json::json!({
+            "backend": backend,
+            "frame": encode(&entry.notify),
+        })),
+        None => serde_json::to_string(&encode(&entry.notify)),
+    }
+}
+
+fn from_line(line: &str) -> serde_json::Result<Entry> {
+    match serde_json::from_str(line)? {
+        serde_json::Value::Object(mut object) => Ok(Entry {
+            backend: serde_json::from_value(object.remove("backend").unwrap_or_default())?,
+            notify: serde_json::from_value(object.remove("frame").unwrap_or_default())?,
+        }),
+        value => Ok(Entry {
+            backend: None,
+            notify: serde_json::from_value(value)?,
+        }),
+    }
+}
+
+/// One entry per line, each ending with a newline.
+fn join_lines<'a>(lines: impl IntoIterator<Item = &'a String>) -> String {
+    lines.into_iter().fold(String::new(), |mut s, line| {
+        writeln!(s, "{line}").unwrap();
+        s
+    })
+}
+
+impl RetryQueue {
+    pub fn new(path: PathBuf, max_entries: usize) -> Self {
+        Self {
+            path,
+            max_entries,
+            lines: Mutex::new(None),
+        }
+    }
+
+    fn read_lines(&self) -> Result<VecDeque<String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(s) => Ok(s.lines().map(String::from).collect()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(VecDeque::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Add `new` after the queued lines, or before them if `front`, keeping
+    /// the newest `max_entries`. Appends to the file unless something is dropped.
+    fn insert_blocking(&self, new: Vec<String>, front: bool) -> Result<()> {
+        let mut guard = self.lines.lock().unwrap();
+
+        let lines = match guard.as_mut() {
+            Some(lines) => lines,
+            None => guard.insert(self.read_lines()?),
+        };
+
+        let appended = new.len();
+
+        match front {
+            true => new
+                .into_iter()
+                .rev()
+                .for_each(|line| lines.push_front(line)),
+            false => lines.extend(new),
+        }
+
+        let dropped = lines.len().saturating_sub(self.max_entries);
+
+        if dropped == 0 && !front {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+
+            file.write_all(join_lines(lines.range(lines.len() - appended..)).as_bytes())?;
+            return Ok(());
+        }
+
+        if dropped != 0 {
+            eprintln!("Retry queue is full, dropping {dropped} oldest entries");
+            lines.drain(..dropped);
+        }
+
+        fs::write(&self.path, join_lines(lines.iter()))?;
+
+        Ok(())
+    }
+
+    /// Append to the queue, dropping the oldest entries beyond `max_entries`.
+    pub async fn push(self: &Arc<Self>, entries: &[Entry]) -> Result<()> {
+        let lines = entries.iter().map(to_line).collect::<Result<Vec<_>, _>>()?;
+
+        let queue = self.clone();
+        tokio::task::spawn_blocking(move || queue.insert_blocking(lines, false)).await?
+    }
+
+    /// Take all entries, oldest first.
+    fn drain_blocking(&self) -> Result<Vec<Entry>> {
+        let mut guard = self.lines.lock().unwrap();
+
+        let lines = match guard.take() {
+            Some(lines) => lines,
+            None => self.read_lines()?,
+        };
+
+        *guard = Some(VecDeque::new());
+
+        let entries = lines
+            .iter()
+            .filter_map(|line| match from_line(line) {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    eprintln!("Skipping broken retry queue entry: {e}");
+                    eprintln!("Buffer: {line}");
+                    None
+                }
+            })
+            .collect();
+
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(entries),
+        }
+    }
+
+    /// Send all queued entries in order, each only to its backend.
+    /// On the first failure, the frames that failed and the rest are queued
+    /// again in one write, ahead of anything pushed meanwhile.
+    pub async fn replay(self: &Arc<Self>, sender: &Sender) -> Result<()> {
+        let queue = self.clone();
+        let entries = tokio::task::spawn_blocking(move || queue.drain_blocking()).await??;
+
+        if !entries.is_empty() {
+            eprintln!("Replaying {} queued entries", entries.len());
+        }
+
+        for (n, entry) in entries.iter().enumerate() {
+            if let Err(e) = sender.deliver(entry).await {
+                eprintln!("{}", e.error);
+
+                let rest = e
+                    .entries
+                    .iter()
+                    .chain(&entries[n + 1..])
+                    .map(to_line)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let queue = self.clone();
+                tokio::task::spawn_blocking(move || queue.insert_blocking(rest, true)).await??;
+
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_retry_queue() {
+    let path = std::env::temp_dir().join(format!("ogenki-retry-queue-{}", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    // Bare strings from earlier versions are for every backend
+    let entries: Vec<_> = (0..4)
+        .map(|n| Entry {
+            backend: (n % 2 == 0).then(|| "url".to_string()),
+            notify: crate::sender::sample_with(2, n),
+        })
+        .collect();
+
+    let line = |n: usize| to_line(&entries[n]).unwrap();
+
+    let queue = RetryQueue::new(path.clone(), 3);
+
+    queue.insert_blocking(vec![line(1)], false).unwrap();
+    queue.insert_blocking(vec![line(2)], false).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    // Put back ahead of what is queued, then trimmed to the newest 3
+    queue.insert_blocking(vec![line(0)], true).unwrap();
+    queue.insert_blocking(vec![line(3)], false).unwrap();
+
+    // A fresh queue reads the file back
+    let queue = RetryQueue::new(path.clone(), 3);
+    assert_eq!(queue.drain_blocking().unwrap(), entries[1..]);
+    assert!(!path.exists());
+    assert_eq!(queue.drain_blocking().unwrap(), []);
+}
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+use twelite_serial::StatusNotify;
+
+use crate::cli::QueueDrop;
+
+/// Bounded queue between the serial loop and the send worker.
+///
+/// When full, [`SendQueue::push`] drops either the oldest queued frame or the
+/// new one, depending on [`QueueDrop`].
+pub struct SendQueue {
+    frames: Mutex<VecDeque<StatusNotify>>,
+    capacity: usize,
+    drop: QueueDrop,
+    ready: Notify,
+    dropped: AtomicU64,
+    busy: AtomicBool,
+}
+
+impl SendQueue {
+    pub fn new(capacity: usize, drop: QueueDrop) -> Self {
+        Self {
+            frames: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            drop,
+            ready: Notify::new(),
+            dropped: AtomicU64::new(0),
+            busy: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns `false` if a frame was dropped to stay within capacity.
+    pub fn push(&self, notify: StatusNotify) -> bool {
+        let mut frames = self.frames.lock().unwrap();
+
+        let kept = match (frames.len() < self.capacity, self.drop) {
+            (true, _) => true,
+            (false, QueueDrop::Newest) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                return false;
+            }
+            (false, QueueDrop::Oldest) => {
+                frames.pop_front();
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        };
+
+        frames.push_back(notify);
+        self.ready.notify_one();
+
+        kept
+    }
+
+    /// Call [`SendQueue::done`] once the frame is handled.
+    pub async fn pop(&self) -> StatusNotify {
+        loop {
+            {
+                let mut frames = self.frames.lock().unwrap();
+
+                if let Some(notify) = frames.pop_front() {
+                    self.busy.store(true, Ordering::Relaxed);
+                    return notify;
+                }
+            }
+
+            self.ready.notified().await;
+        }
+    }
+
+    pub fn done(&self) {
+        self.busy.store(false, Ordering::Relaxed);
+    }
+
+    /// Frames queued or being sent.
+    pub fn pending(&self) -> usize {
+        let frames = self.frames.lock().unwrap();
+        frames.len() + self.busy.load(Ordering::Relaxed) as usize
+    }
+
+    /// Dropped frames since the last call.
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+#[test]
+fn test_send_queue() {
+    let frames: Vec<_> = (0..3).map(|n| crate::sender::sample_with(2, n)).collect();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let queue = SendQueue::new(2, QueueDrop::Oldest);
+    assert!(queue.push(frames[0]));
+    assert!(queue.push(frames[1]));
+    assert!(!queue.push(frames[2]));
+    assert_eq!(queue.take_dropped(), 1);
+    assert_eq!(queue.take_dropped(), 0);
+    assert_eq!(runtime.block_on(queue.pop()), frames[1]);
+    assert_eq!(queue.pending(), 2);
+    queue.done();
+    assert_eq!(queue.pending(), 1);
+    assert_eq!(runtime.block_on(queue.pop()), frames[2]);
+    queue.done();
+    assert_eq!(queue.pending(), 0);
+
+    let queue = SendQueue::new(2, QueueDrop::Newest);
+    assert!(queue.push(frames[0]));
+    assert!(queue.push(frames[1]));
+    assert!(!queue.push(frames[2]));
+    assert_eq!(queue.take_dropped(), 1);
+    assert_eq!(runtime.block_on(queue.pop()), frames[0]);
+    assert_eq!(runtime.block_on(queue.pop()), frames[1]);
+}
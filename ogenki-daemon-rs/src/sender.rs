@@ -1,69 +1,1520 @@
-use anyhow::Result;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{anyhow, bail, Context, Result};
+use futures_util::future::join_all;
+use reqwest::header::HeaderMap;
 use twelite_serial::StatusNotify;
 
+use crate::cli::{MqttPayloadFormat, PayloadFormat, UdpFormat};
+use crate::format::{FormatExt, CSV_HEADER};
+use crate::retry_queue::{Entry, RetryQueue};
+use crate::spool::Spool;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A destination for decoded frames. See [`Sender`].
+pub trait Backend {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>>;
+
+    /// Deliver anything buffered, after deliveries already under way.
+    /// Called every `--batch-interval-ms` when batching, and once on shutdown.
+    fn flush(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Every frame a backend failed to deliver, when that is more than the one
+/// passed to [`Backend::send`], or any at all for [`Backend::flush`].
+#[derive(Debug)]
+pub struct FailedFrames {
+    pub frames: Vec<StatusNotify>,
+    pub error: anyhow::Error,
+}
+
+impl std::fmt::Display for FailedFrames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for FailedFrames {}
+
+/// Frames waiting for `--batch-size` or `--batch-interval-ms`.
+struct Batch {
+    frames: Mutex<Vec<StatusNotify>>,
+    /// Held while a batch taken by [`Backend::flush`] is delivered.
+    flushing: tokio::sync::Mutex<()>,
+}
+
+/// Posts each frame as a multipart form, or with `--batch-size` above 1,
+/// batches of frames as a JSON array.
+#[derive(Clone)]
 pub struct WebBackend {
     client: reqwest::Client,
     backend: crate::cli::Backend,
+    spool: Option<Arc<Spool>>,
+    batch: Option<Arc<Batch>>,
+    headers: HeaderMap,
 }
 
 impl WebBackend {
-    fn new_from_backend(backend: &crate::cli::Backend) -> Self {
-        let client = reqwest::Client::new();
+    fn client(backend: &crate::cli::Backend) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+
+        if let (Some(cert), Some(key)) = (&backend.client_cert, &backend.client_key) {
+            let mut pem = fs::read(cert).with_context(|| format!("{}", cert.display()))?;
+            pem.extend(fs::read(key).with_context(|| format!("{}", key.display()))?);
+
+            let identity = reqwest::Identity::from_pem(&pem)
+                .with_context(|| format!("{} and {}", cert.display(), key.display()))?;
+
+            builder = builder.identity(identity);
+        }
+
+        if let Some(ca) = &backend.ca_cert {
+            let pem = fs::read(ca).with_context(|| format!("{}", ca.display()))?;
+
+            for cert in reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("{}", ca.display()))?
+            {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        if backend.danger_accept_invalid_certs {
+            eprintln!("Warning: server certificates are not verified.");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder.build()?)
+    }
+
+    fn new_from_backend(backend: &crate::cli::Backend) -> Result<Self> {
+        let client = Self::client(backend)?;
         let backend = backend.clone();
 
-        Self { client, backend }
+        let spool = match &backend.spool_dir {
+            Some(dir) => Some(Arc::new(Spool::new(dir, backend.spool_max_bytes)?)),
+            None => None,
+        };
+
+        let batch = match backend.batch_size {
+            0 | 1 => None,
+            n => Some(Arc::new(Batch {
+                frames: Mutex::new(Vec::with_capacity(n)),
+                flushing: tokio::sync::Mutex::new(()),
+            })),
+        };
+
+        let headers = backend.headers.iter().cloned().collect();
+
+        let web = Self {
+            client,
+            backend,
+            spool,
+            batch,
+            headers,
+        };
+
+        if let Some(spool) = web.spool.clone() {
+            let web = Self {
+                spool: None,
+                batch: None,
+                ..web.clone()
+            };
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+                loop {
+                    interval.tick().await;
+
+                    if let Err(e) = spool.replay(&web).await {
+                        eprintln!("Failed to replay spool: {e:?}");
+                    }
+                }
+            });
+        }
+
+        Ok(web)
     }
 
-    async fn send(&self, notify: &StatusNotify) -> Result<()> {
+    /// `frames[0]` as a multipart form or a JSON object, following `--payload-format`,
+    /// or a JSON array of `frames` when batching.
+    fn request(&self, frames: &[StatusNotify]) -> reqwest::RequestBuilder {
         let url = self.backend.url.as_ref().unwrap();
 
-        let ctx = self.client.post(url.to_string());
+        // Auth is applied after, so it wins over a custom Authorization header.
+        let ctx = self
+            .client
+            .post(url.to_string())
+            .headers(self.headers.clone());
 
-        let ctx = match self.backend.username {
-            Some(_) => ctx.basic_auth(
+        let ctx = match (&self.backend.token, &self.backend.username) {
+            (Some(token), _) => ctx.bearer_auth(token),
+            (None, Some(_)) => ctx.basic_auth(
                 self.backend.username.as_ref().unwrap(),
                 self.backend.password.as_ref(),
             ),
-            None => ctx,
+            (None, None) => ctx,
         };
 
-        ctx.multipart(
-            reqwest::multipart::Form::new()
-                .text("wireless", notify.lqi().to_string())
-                .text("battery", notify.power_voltage_millis().to_string())
-                .text("doorsensor", notify.di_status().to_string())
-                .text("status", notify.di1_status().to_string())
-                .text("changed", notify.di1_changed().to_string()),
-        )
-        .send()
-        .await?
-        .error_for_status()?;
+        if self.backend.batch_size > 1 {
+            return ctx
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_string(frames).unwrap());
+        }
+
+        let notify = &frames[0];
+
+        if let PayloadFormat::Json = self.backend.payload_format {
+            return ctx
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_string(notify).unwrap());
+        }
+
+        let form = reqwest::multipart::Form::new()
+            .text("wireless", notify.lqi().to_string())
+            .text("battery", notify.power_voltage_millis().to_string())
+            .text("doorsensor", notify.di_status().to_string())
+            .text("status", notify.di1_status().to_string())
+            .text("changed", notify.di1_changed().to_string())
+            .text("hardware_id", format!("{:08X}", notify.hardware_id()))
+            .text("lqi_dbm", notify.lqi_dbm().to_string())
+            .text("timestamp", notify.timestamp().to_string());
+
+        let form = notify
+            .di_statuses()
+            .into_iter()
+            .zip(notify.di_changes())
+            .enumerate()
+            .fold(form, |form, (n, (status, changed))| {
+                form.text(format!("di{}", n + 1), status.to_string())
+                    .text(format!("di{}_changed", n + 1), changed.to_string())
+            });
+
+        // An invalid AD channel is sent as an empty value.
+        let form = (1..=4).fold(form, |form, ch| {
+            let mv = notify.ad_voltage_at_channel(ch);
+            form.text(
+                format!("ad{ch}_mv"),
+                mv.map(|mv| mv.to_string()).unwrap_or_default(),
+            )
+        });
+
+        ctx.multipart(form)
+    }
+
+    /// `base * 2^attempt`, plus up to half of that again as jitter.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .backend
+            .retry_base_delay_ms
+            .saturating_mul(1 << attempt.min(16));
+
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .subsec_nanos() as u64;
+
+        Duration::from_millis(delay + nanos % (delay / 2 + 1))
+    }
+
+    /// Network errors and 5xx are retried up to `--max-retries` times, 4xx is not.
+    async fn post(&self, frames: &[StatusNotify]) -> reqwest::Result<()> {
+        let mut attempt = 0;
+
+        loop {
+            let result = match self.request(frames).send().await {
+                Ok(response) => response.error_for_status().map(|_| ()),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e)
+                    if attempt < self.backend.max_retries
+                        && e.status().is_none_or(|s| s.is_server_error()) =>
+                {
+                    eprintln!("{e}, retrying");
+                    tokio::time::sleep(self.retry_delay(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Post `frames` as a unit. If that still fails with a network error or
+    /// 5xx after all retries, they all go to the spool if `--spool-dir` is given.
+    async fn deliver(&self, frames: &[StatusNotify]) -> Result<()> {
+        match (self.post(frames).await, &self.spool) {
+            (Ok(()), _) => Ok(()),
+            (Err(e), Some(spool)) if e.status().is_none_or(|s| s.is_server_error()) => {
+                eprintln!("{e}, spooled {} frames", frames.len());
+                spool.push(frames).await
+            }
+            (Err(e), _) => Err(e.into()),
+        }
+    }
+
+    /// [`Self::deliver`] a taken batch, failing with all of its frames.
+    async fn deliver_batch(&self, frames: Vec<StatusNotify>) -> Result<()> {
+        match self.deliver(&frames).await {
+            Ok(()) => Ok(()),
+            Err(error) => Err(FailedFrames { frames, error }.into()),
+        }
+    }
+}
+
+impl Backend for WebBackend {
+    /// When batching, the frame is only buffered, and is posted once the
+    /// batch is full or on the next [`Backend::flush`]. A failed batch fails
+    /// with [`FailedFrames`].
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let Some(batch) = &self.batch else {
+                return self.deliver(std::slice::from_ref(notify)).await;
+            };
+
+            let frames = {
+                let mut frames = batch.frames.lock().unwrap();
+                frames.push(*notify);
+
+                if frames.len() < self.backend.batch_size {
+                    return Ok(());
+                }
+
+                std::mem::take(&mut *frames)
+            };
+
+            self.deliver_batch(frames).await
+        })
+    }
+
+    fn flush(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let Some(batch) = &self.batch else {
+                return Ok(());
+            };
+
+            let _flushing = batch.flushing.lock().await;
+            let frames = std::mem::take(&mut *batch.frames.lock().unwrap());
+
+            match frames.is_empty() {
+                true => Ok(()),
+                false => self.deliver_batch(frames).await,
+            }
+        })
+    }
+}
+
+pub struct UdpBackend {
+    socket: tokio::net::UdpSocket,
+    format: UdpFormat,
+    tags: bool,
+    influx_tags: Vec<(String, String)>,
+}
+
+impl UdpBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Result<Self> {
+        let addr = backend.udp_addr.as_ref().unwrap();
+
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .with_context(|| format!("{addr} is not resolved"))?;
+
+        let socket = match addr.is_ipv4() {
+            true => std::net::UdpSocket::bind("0.0.0.0:0")?,
+            false => std::net::UdpSocket::bind("[::]:0")?,
+        };
+
+        socket.connect(addr)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket: tokio::net::UdpSocket::from_std(socket)?,
+            format: backend.udp_format,
+            tags: backend.statsd_tags,
+            influx_tags: backend.influx_tags.clone(),
+        })
+    }
+}
+
+impl Backend for UdpBackend {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let payload = match self.format {
+                UdpFormat::Statsd => notify.to_statsd_lines(self.tags),
+                UdpFormat::Influx => {
+                    let tags: Vec<_> = self
+                        .influx_tags
+                        .iter()
+                        .map(|(k, v)| (k.as_str(), v.as_str()))
+                        .collect();
+
+                    notify.to_line_protocol_with_tags(&tags)
+                }
+            };
+
+            self.socket.send(payload.as_bytes()).await?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Connect and write timeout of [`OpenTsdbBackend`].
+const OPENTSDB_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// OpenTSDB telnet-style API. The connection is kept open between frames,
+/// and reopened once if writing to it fails.
+pub struct OpenTsdbBackend {
+    addr: String,
+    prefix: String,
+    stream: tokio::sync::Mutex<Option<tokio::net::TcpStream>>,
+}
+
+impl OpenTsdbBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Self {
+        Self {
+            addr: backend.opentsdb_addr.clone().unwrap(),
+            prefix: backend.opentsdb_prefix.clone(),
+            stream: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> Result<tokio::net::TcpStream> {
+        tokio::time::timeout(OPENTSDB_TIMEOUT, tokio::net::TcpStream::connect(&self.addr))
+            .await
+            .with_context(|| format!("Connecting to {} timed out", self.addr))?
+            .with_context(|| format!("Failed to connect to {}", self.addr))
+    }
+
+    async fn write(stream: &mut tokio::net::TcpStream, payload: &[u8]) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        tokio::time::timeout(OPENTSDB_TIMEOUT, stream.write_all(payload))
+            .await
+            .context("Writing to OpenTSDB timed out")??;
+
+        Ok(())
+    }
+}
+
+impl Backend for OpenTsdbBackend {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let unix_secs = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs();
+
+            let payload = notify.to_opentsdb_put_lines(&self.prefix, unix_secs);
+
+            let mut stream = self.stream.lock().await;
+
+            if let Some(open) = stream.as_mut() {
+                if Self::write(open, payload.as_bytes()).await.is_ok() {
+                    return Ok(());
+                }
+            }
+
+            *stream = None;
+
+            let mut open = self.connect().await?;
+            Self::write(&mut open, payload.as_bytes()).await?;
+            *stream = Some(open);
+
+            Ok(())
+        })
+    }
+}
+
+/// Posts [`StatusNotify::to_slack_message`] to an incoming webhook,
+/// only for frames with a DI change.
+pub struct SlackBackend {
+    client: reqwest::Client,
+    url: reqwest::Url,
+}
+
+impl SlackBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: backend.slack_webhook_url.clone().unwrap(),
+        }
+    }
+}
+
+impl Backend for SlackBackend {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if notify.di_changed() & 0x0F == 0 {
+                return Ok(());
+            }
+
+            let name = format!("{:08X}", notify.hardware_id());
+
+            self.client
+                .post(self.url.clone())
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(notify.to_slack_message(&name))
+                .send()
+                .await?
+                .error_for_status()?;
+
+            Ok(())
+        })
+    }
+}
+
+/// How long to wait before polling the MQTT event loop again after an error,
+/// which is also the reconnect interval.
+const MQTT_RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Publishes [`FormatExt::to_mqtt_payload`] with QoS 0.
+/// `{hardware_id}` in the topic is replaced with the hex hardware id.
+///
+/// The connection is driven by a background task, which reconnects on disconnect.
+pub struct MqttBackend {
+    client: rumqttc::AsyncClient,
+    /// Set by the event loop between a CONNACK and the next connection error.
+    connected: Arc<AtomicBool>,
+    topic: String,
+    format: MqttPayloadFormat,
+}
+
+impl MqttBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Result<Self> {
+        let url = backend.mqtt_url.as_ref().unwrap();
+
+        let host = url
+            .host_str()
+            .with_context(|| format!("{url} has no host"))?;
+
+        let mut options = rumqttc::MqttOptions::new(
+            format!("ogenki-daemon-rs-{}", std::process::id()),
+            host,
+            url.port().unwrap_or(1883),
+        );
+
+        options.set_keep_alive(Duration::from_secs(30));
+
+        if let Some(username) = &backend.username {
+            options.set_credentials(username, backend.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut eventloop) = rumqttc::AsyncClient::new(options, 64);
+        let connected = Arc::new(AtomicBool::new(false));
+
+        tokio::spawn({
+            let connected = connected.clone();
+
+            async move {
+                loop {
+                    match eventloop.poll().await {
+                        Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => {
+                            connected.store(true, Ordering::Relaxed);
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            connected.store(false, Ordering::Relaxed);
+                            eprintln!("MQTT: {e}");
+                            tokio::time::sleep(MQTT_RECONNECT_INTERVAL).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            client,
+            connected,
+            topic: backend.mqtt_topic.clone(),
+            format: backend.mqtt_payload_format,
+        })
+    }
+}
+
+impl Backend for MqttBackend {
+    /// Fails at once while the broker is disconnected or the client's request
+    /// channel is full, instead of waiting for it and holding up every other backend.
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            if !self.connected.load(Ordering::Relaxed) {
+                bail!("MQTT broker is disconnected");
+            }
+
+            let topic = self
+                .topic
+                .replace("{hardware_id}", &format!("{:08X}", notify.hardware_id()));
+
+            self.client.try_publish(
+                topic,
+                rumqttc::QoS::AtMostOnce,
+                false,
+                notify.to_mqtt_payload(self.format),
+            )?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Appends [`FormatExt::to_json_line`] to a local file.
+/// The file is reopened if it was rotated away or a write failed.
+pub struct FileBackend {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+}
+
+impl FileBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Self {
+        Self {
+            path: backend.log_file.clone().unwrap(),
+            file: Mutex::new(None),
+        }
+    }
+
+    fn open(&self) -> Result<File> {
+        Ok(OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?)
+    }
+
+    #[cfg(unix)]
+    fn is_rotated(&self, file: &File) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        match (fs::metadata(&self.path), file.metadata()) {
+            (Ok(path), Ok(file)) => (path.dev(), path.ino()) != (file.dev(), file.ino()),
+            _ => true,
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn is_rotated(&self, _file: &File) -> bool {
+        fs::metadata(&self.path).is_err()
+    }
+
+    fn write(&self, notify: &StatusNotify) -> Result<()> {
+        let line = format!("{}\n", notify.to_json_line(SystemTime::now()));
+        let mut file = self.file.lock().unwrap();
+
+        if let Some(f) = file.as_mut() {
+            if !self.is_rotated(f) && f.write_all(line.as_bytes()).and_then(|_| f.flush()).is_ok() {
+                return Ok(());
+            }
+        }
+
+        *file = None;
+
+        let mut f = self.open()?;
+        f.write_all(line.as_bytes())?;
+        f.flush()?;
+        *file = Some(f);
 
         Ok(())
     }
 }
 
-pub enum Sender {
-    Web(WebBackend),
-    Nothing,
+/// Shared with the blocking thread pool, where the file is written.
+impl Backend for Arc<FileBackend> {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        let backend = self.clone();
+        let notify = *notify;
+
+        Box::pin(async move { tokio::task::spawn_blocking(move || backend.write(&notify)).await? })
+    }
+}
+
+/// Appends [`FormatExt::to_csv_row`] to a local file,
+/// writing [`CSV_HEADER`] first if the file is empty.
+pub struct CsvBackend {
+    path: PathBuf,
+    writer: Mutex<Option<csv::Writer<File>>>,
+}
+
+impl CsvBackend {
+    fn new_from_backend(backend: &crate::cli::Backend) -> Self {
+        Self {
+            path: backend.csv_file.clone().unwrap(),
+            writer: Mutex::new(None),
+        }
+    }
+
+    fn open(&self) -> Result<csv::Writer<File>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        let empty = file.metadata()?.len() == 0;
+        let mut writer = csv::Writer::from_writer(file);
+
+        if empty {
+            writer.write_record(CSV_HEADER)?;
+            writer.flush()?;
+        }
+
+        Ok(writer)
+    }
+
+    fn write(&self, notify: &StatusNotify) -> Result<()> {
+        let row = notify.to_csv_row(SystemTime::now());
+        let mut writer = self.writer.lock().unwrap();
+
+        let w = match writer.as_mut() {
+            Some(w) => w,
+            None => writer.insert(self.open()?),
+        };
+
+        if let Err(e) = w
+            .write_record(&row)
+            .and_then(|_| w.flush().map_err(csv::Error::from))
+        {
+            *writer = None;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Shared with the blocking thread pool, where the file is written.
+impl Backend for Arc<CsvBackend> {
+    fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+        let backend = self.clone();
+        let notify = *notify;
+
+        Box::pin(async move { tokio::task::spawn_blocking(move || backend.write(&notify)).await? })
+    }
+}
+
+/// Sends each frame to every backend concurrently.
+///
+/// All backends are always attempted. With `--backend-retry-queue-file`, every
+/// frame that failed, a whole batch for a batching backend, is queued to be
+/// replayed on the next start, to the backend that failed only.
+pub struct Sender {
+    /// Named after the option that enables each, as recorded in the retry queue.
+    backends: Vec<(String, Box<dyn Backend + Send + Sync>)>,
+    retry_queue: Option<Arc<RetryQueue>>,
+}
+
+/// What the failed backends could not deliver, with every error in one.
+#[derive(Debug)]
+pub struct SendFailure {
+    pub entries: Vec<Entry>,
+    pub error: anyhow::Error,
 }
 
 impl Sender {
     pub fn new(backend: &crate::cli::Backend) -> Self {
-        match backend.url {
-            None => {
-                println!("Warning: backend is not specified.");
-                println!("         entering dry-run mode.");
-                Self::Nothing
+        let mut backends: Vec<(String, Box<dyn Backend + Send + Sync>)> = vec![];
+
+        if backend.log_file.is_some() {
+            backends.push((
+                "log-file".to_string(),
+                Box::new(Arc::new(FileBackend::new_from_backend(backend))),
+            ));
+        }
+
+        if backend.csv_file.is_some() {
+            backends.push((
+                "csv-file".to_string(),
+                Box::new(Arc::new(CsvBackend::new_from_backend(backend))),
+            ));
+        }
+
+        if backend.url.is_some() {
+            backends.push((
+                "url".to_string(),
+                Box::new(
+                    WebBackend::new_from_backend(backend).expect("Failed to set up web backend"),
+                ),
+            ));
+        }
+
+        for (n, target) in backend.web_targets.iter().enumerate() {
+            let name = format!("backend{}", n + 1);
+
+            let backend = crate::cli::Backend {
+                url: Some(target.url.clone()),
+                username: target.username.clone(),
+                password: target.password.clone(),
+                token: None,
+                spool_dir: backend.spool_dir.as_ref().map(|dir| dir.join(&name)),
+                ..backend.clone()
+            };
+
+            backends.push((
+                name,
+                Box::new(
+                    WebBackend::new_from_backend(&backend).expect("Failed to set up web backend"),
+                ),
+            ));
+        }
+
+        if backend.udp_addr.is_some() {
+            backends.push((
+                "udp-addr".to_string(),
+                Box::new(UdpBackend::new_from_backend(backend).expect("Failed to open UDP socket")),
+            ));
+        }
+
+        if backend.mqtt_url.is_some() {
+            backends.push((
+                "mqtt-url".to_string(),
+                Box::new(MqttBackend::new_from_backend(backend).expect("Failed to parse MQTT URL")),
+            ));
+        }
+
+        if backend.slack_webhook_url.is_some() {
+            backends.push((
+                "slack-webhook-url".to_string(),
+                Box::new(SlackBackend::new_from_backend(backend)),
+            ));
+        }
+
+        if backend.opentsdb_addr.is_some() {
+            backends.push((
+                "opentsdb-addr".to_string(),
+                Box::new(OpenTsdbBackend::new_from_backend(backend)),
+            ));
+        }
+
+        if backends.is_empty() {
+            eprintln!("Warning: backend is not specified.");
+            eprintln!("         entering dry-run mode.");
+        }
+
+        let retry_queue = backend
+            .backend_retry_queue_file
+            .clone()
+            .map(|path| Arc::new(RetryQueue::new(path, backend.retry_queue_max_entries)));
+
+        Self {
+            backends,
+            retry_queue,
+        }
+    }
+
+    /// Wait for one future per backend, or only the one named `only`,
+    /// collecting every failure. A backend failing without [`FailedFrames`]
+    /// failed on `frames`.
+    async fn all<'a>(
+        &'a self,
+        only: Option<&str>,
+        f: impl Fn(&'a (dyn Backend + Send + Sync)) -> BoxFuture<'a, Result<()>>,
+        frames: &[StatusNotify],
+    ) -> Result<(), SendFailure> {
+        let backends: Vec<_> = self
+            .backends
+            .iter()
+            .filter(|(name, _)| only.is_none_or(|only| name == only))
+            .collect();
+
+        let results = join_all(backends.iter().map(|(_, b)| f(b.as_ref()))).await;
+
+        let mut entries = vec![];
+        let mut errors = vec![];
+
+        for ((name, _), result) in backends.iter().zip(results) {
+            let Err(e) = result else {
+                continue;
+            };
+
+            let (frames, error) = match e.downcast::<FailedFrames>() {
+                Ok(e) => (e.frames, e.error),
+                Err(e) => (frames.to_vec(), e),
+            };
+
+            entries.extend(frames.into_iter().map(|notify| Entry {
+                backend: Some(name.clone()),
+                notify,
+            }));
+
+            errors.push(error);
+        }
+
+        let error = match errors.len() {
+            0 => return Ok(()),
+            1 if backends.len() == 1 => errors.pop().unwrap(),
+            n => anyhow!(
+                "{n} of {} backends failed: {}",
+                backends.len(),
+                errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+        };
+
+        Err(SendFailure { entries, error })
+    }
+
+    /// Queue failed frames for retry, if there is a retry queue.
+    async fn queue(&self, result: Result<(), SendFailure>) -> Result<()> {
+        let Err(SendFailure { entries, error }) = result else {
+            return Ok(());
+        };
+
+        if let Some(retry_queue) = &self.retry_queue {
+            if let Err(e) = retry_queue.push(&entries).await {
+                eprintln!("Failed to push to retry queue: {e:#}");
             }
-            Some(_) => Self::Web(WebBackend::new_from_backend(backend)),
         }
+
+        Err(error)
+    }
+
+    /// Send a queued entry to its backend without queueing failures.
+    /// See [`RetryQueue::replay`].
+    pub async fn deliver(&self, entry: &Entry) -> Result<(), SendFailure> {
+        let only = entry.backend.as_deref();
+
+        if only.is_some_and(|only| self.backends.iter().all(|(name, _)| name != only)) {
+            eprintln!(
+                "Dropping queued frame for {}: not configured",
+                only.unwrap()
+            );
+            return Ok(());
+        }
+
+        self.all(
+            only,
+            |b| b.send(&entry.notify),
+            std::slice::from_ref(&entry.notify),
+        )
+        .await
     }
 
     pub async fn send(&self, notify: &StatusNotify) -> Result<()> {
-        match self {
-            Self::Web(backend) => backend.send(notify).await,
-            Self::Nothing => Ok(()),
+        let result = self
+            .all(None, |b| b.send(notify), std::slice::from_ref(notify))
+            .await;
+
+        self.queue(result).await
+    }
+
+    pub async fn flush(&self) -> Result<()> {
+        self.queue(self.all(None, |b| b.flush(), &[]).await).await
+    }
+
+    /// Send what the retry queue holds from earlier runs.
+    pub async fn replay(&self) -> Result<()> {
+        match &self.retry_queue {
+            Some(retry_queue) => retry_queue.replay(self).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[test]
+fn test_file_backend() {
+    let dir = std::env::temp_dir().join(format!("ogenki-file-backend-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("log.jsonl");
+    let rotated = dir.join("log.jsonl.1");
+
+    let backend = FileBackend {
+        path: path.clone(),
+        file: Mutex::new(None),
+    };
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    backend.write(&notify).unwrap();
+    fs::rename(&path, &rotated).unwrap();
+    backend.write(&notify).unwrap();
+    backend.write(&notify).unwrap();
+
+    assert_eq!(fs::read_to_string(&rotated).unwrap().lines().count(), 1);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_csv_backend() {
+    let dir = std::env::temp_dir().join(format!("ogenki-csv-backend-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("log.csv");
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    for _ in 0..2 {
+        let backend = CsvBackend {
+            path: path.clone(),
+            writer: Mutex::new(None),
+        };
+
+        backend.write(&notify).unwrap();
+    }
+
+    let mut reader = csv::Reader::from_path(&path).unwrap();
+    assert_eq!(reader.headers().unwrap(), CSV_HEADER.as_slice());
+
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 2);
+
+    for row in &rows {
+        assert_eq!(row.len(), CSV_HEADER.len());
+        assert_eq!(
+            row.iter().skip(1).collect::<Vec<_>>(),
+            [
+                "81000038", "117", "-57.55", "3076", "1", "0", "0", "0", "4092", "4092", "4092",
+                "4092",
+            ],
+        );
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sender() {
+    struct FailingBackend;
+
+    impl Backend for FailingBackend {
+        fn send<'a>(&'a self, _notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Err(anyhow!("unreachable")) })
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("ogenki-multi-backend-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("log.jsonl");
+    let queue_path = dir.join("queue.jsonl");
+
+    let sender = Sender {
+        backends: vec![
+            ("failing".to_string(), Box::new(FailingBackend)),
+            (
+                "log-file".to_string(),
+                Box::new(Arc::new(FileBackend {
+                    path: path.clone(),
+                    file: Mutex::new(None),
+                })),
+            ),
+        ],
+        retry_queue: Some(Arc::new(RetryQueue::new(queue_path.clone(), 10))),
+    };
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let result = runtime.block_on(sender.send(&notify));
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "1 of 2 backends failed: unreachable"
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+    // Only the failed backend is retried, so the file gets no duplicate
+    assert_eq!(
+        fs::read_to_string(&queue_path).unwrap(),
+        "{\"backend\":\"failing\",\"frame\":\":7881150175810000380026C9000C04220000FFFFFFFFFFA7\"}\n"
+    );
+
+    runtime.block_on(sender.replay()).unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    assert_eq!(fs::read_to_string(&queue_path).unwrap().lines().count(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_udp_backend() {
+    use clap::Parser;
+
+    let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = receiver.local_addr().unwrap().to_string();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+
+    let backend = crate::cli::Backend::parse_from(["test", "--udp-addr", &addr]);
+    let backend = runtime.block_on(async { UdpBackend::new_from_backend(&backend).unwrap() });
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    runtime.block_on(backend.send(&notify)).unwrap();
+
+    let mut buf = [0; 1024];
+    let len = receiver.recv(&mut buf).unwrap();
+    assert_eq!(buf[..len], *notify.to_statsd_lines(false).as_bytes());
+}
+
+#[test]
+fn test_mqtt_backend() {
+    use clap::Parser;
+    use std::io::{Read, Write as _};
+
+    fn read_packet(stream: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+        let mut header = [0; 1];
+        stream.read_exact(&mut header).unwrap();
+
+        let (mut len, mut shift) = (0, 0);
+        loop {
+            let mut byte = [0; 1];
+            stream.read_exact(&mut byte).unwrap();
+            len |= usize::from(byte[0] & 0x7F) << shift;
+            shift += 7;
+
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+
+        let mut body = vec![0; len];
+        stream.read_exact(&mut body).unwrap();
+        (header[0], body)
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("mqtt://{}", listener.local_addr().unwrap());
+
+    let broker = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+
+        let (header, connect) = read_packet(&mut stream);
+        assert_eq!(header, 0x10);
+        stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+        let (header, publish) = read_packet(&mut stream);
+        assert_eq!(header & 0xF0, 0x30);
+        (connect, publish)
+    });
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let backend =
+        crate::cli::Backend::parse_from(["test", "--mqtt-url", &url, "--username", "user"]);
+    let backend = runtime.block_on(async { MqttBackend::new_from_backend(&backend).unwrap() });
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // Not connected until the event loop has seen the CONNACK
+    assert!(runtime.block_on(backend.send(&notify)).is_err());
+
+    runtime.block_on(async {
+        while backend.send(&notify).await.is_err() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+
+    let (connect, publish) = runtime
+        .block_on(async { tokio::task::spawn_blocking(|| broker.join().unwrap()).await })
+        .unwrap();
+
+    // Username flag only, as no password was given
+    assert_eq!(connect[7] & 0xC0, 0x80);
+    assert!(connect.ends_with(b"\x00\x04user"));
+
+    let topic = b"\x00\x0Fogenki/81000038";
+    assert!(publish.starts_with(topic));
+    assert_eq!(
+        publish[topic.len()..],
+        notify.to_mqtt_payload(MqttPayloadFormat::Json)
+    );
+}
+
+#[test]
+fn test_opentsdb_backend() {
+    use clap::Parser;
+    use std::io::{BufRead, BufReader};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let backend = OpenTsdbBackend::new_from_backend(&crate::cli::Backend::parse_from([
+        "test",
+        "--opentsdb-addr",
+        &addr,
+    ]));
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(backend.send(&notify)).unwrap();
+    runtime.block_on(backend.send(&notify)).unwrap();
+
+    // Both frames arrive on one connection
+    let (stream, _) = listener.accept().unwrap();
+    let lines = notify.to_opentsdb_put_lines("twelite", 0).lines().count();
+
+    let puts = BufReader::new(stream)
+        .lines()
+        .take(lines * 2)
+        .map(|line| line.unwrap())
+        .filter(|line| line.starts_with("put twelite."))
+        .count();
+
+    assert_eq!(puts, lines * 2);
+}
+
+/// The sample frame with byte `n` set to `value`. The checksum is left as is.
+#[cfg(test)]
+pub fn sample_with(n: usize, value: u8) -> StatusNotify {
+    let mut buf = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7")
+        .unwrap()
+        .into_bytes();
+
+    buf[n] = value;
+    StatusNotify::from_bytes(buf)
+}
+
+/// Answer one HTTP request on `listener` with `status`, returning its headers and body.
+#[cfg(test)]
+fn respond_once(listener: &std::net::TcpListener, status: &str) -> (String, Vec<u8>) {
+    use std::io::{BufRead, BufReader, Read};
+
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(&stream);
+    let mut headers = String::new();
+    let mut content_length = 0;
+
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+
+        if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+            content_length = v.trim().parse().unwrap();
+        }
+
+        if line == "\r\n" {
+            break;
+        }
+
+        headers.push_str(&line);
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    write!(
+        &stream,
+        "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+
+    (headers, body)
+}
+
+/// Send `frames` through the backend built by `new` from `args`, where `{url}`
+/// is the URL of a local server, and flush it.
+/// Returns the one request the server received.
+#[cfg(test)]
+fn capture_request<B: Backend>(
+    args: &[&str],
+    new: impl FnOnce(&crate::cli::Backend) -> B,
+    frames: &[StatusNotify],
+) -> (String, Vec<u8>) {
+    use clap::Parser;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    let server = std::thread::spawn(move || respond_once(&listener, "200 OK"));
+
+    let args = args.iter().map(|arg| arg.replace("{url}", &url));
+    let backend = crate::cli::Backend::parse_from(std::iter::once("test".to_string()).chain(args));
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let backend = new(&backend);
+
+            for notify in frames {
+                backend.send(notify).await.unwrap();
+            }
+
+            backend.flush().await.unwrap();
+        });
+
+    server.join().unwrap()
+}
+
+#[test]
+fn test_web_backend_retry() {
+    use std::net::TcpListener;
+
+    use clap::Parser;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    let server = std::thread::spawn(move || {
+        for status in [
+            "500 Internal Server Error",
+            "502 Bad Gateway",
+            "200 OK",
+            "400 Bad Request",
+        ] {
+            respond_once(&listener, status);
         }
+    });
+
+    let backend = crate::cli::Backend::parse_from([
+        "test",
+        &url,
+        "--max-retries",
+        "2",
+        "--retry-base-delay-ms",
+        "1",
+    ]);
+
+    let backend = WebBackend::new_from_backend(&backend).unwrap();
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(backend.send(&notify)).unwrap();
+
+    // 4xx is not retried
+    assert!(runtime.block_on(backend.send(&notify)).is_err());
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_web_backend_batch() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // One request for both frames
+    let (headers, body) = capture_request(
+        &["{url}", "--batch-size", "2", "--batch-interval-ms", "60000"],
+        |b| WebBackend::new_from_backend(b).unwrap(),
+        &[notify, notify],
+    );
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert!(headers.contains("content-type: application/json"));
+    assert_eq!(body.as_array().unwrap().len(), 2);
+    assert_eq!(body[1]["hardware_id"], 0x81000038u32);
+}
+
+#[test]
+fn test_web_backend_batch_retry_queue() {
+    use clap::Parser;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || respond_once(&listener, "400 Bad Request"));
+
+    let path = std::env::temp_dir().join(format!("ogenki-batch-retry-{}", std::process::id()));
+    let _ = fs::remove_file(&path);
+
+    let backend = crate::cli::Backend::parse_from([
+        "test",
+        &url,
+        "--batch-size",
+        "2",
+        "--backend-retry-queue-file",
+        path.to_str().unwrap(),
+    ]);
+
+    let frames = [sample_with(2, 0), sample_with(2, 1)];
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let sender = Sender::new(&backend);
+
+            sender.send(&frames[0]).await.unwrap();
+            assert!(sender.send(&frames[1]).await.is_err());
+        });
+
+    server.join().unwrap();
+
+    // The whole batch is queued, not just the frame which filled it
+    let queued: Vec<_> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let line: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(line["backend"], "url");
+            StatusNotify::decode_str(line["frame"].as_str().unwrap()).unwrap()
+        })
+        .collect();
+
+    assert_eq!(queued, frames);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_web_backend_flush() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // One frame never fills the batch, so flush posts it
+    let (_, body) = capture_request(
+        &["{url}", "--batch-size", "2", "--batch-interval-ms", "60000"],
+        |b| WebBackend::new_from_backend(b).unwrap(),
+        &[notify],
+    );
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_web_backend_form() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    let (_, body) = capture_request(
+        &["{url}"],
+        |b| WebBackend::new_from_backend(b).unwrap(),
+        &[notify],
+    );
+    let body = String::from_utf8(body).unwrap();
+
+    let field = |name: &str| {
+        let header = format!("name=\"{name}\"\r\n\r\n");
+        let start = body
+            .find(&header)
+            .unwrap_or_else(|| panic!("{name} is missing"))
+            + header.len();
+        body[start..].split("\r\n").next().unwrap().to_string()
+    };
+
+    assert_eq!(field("wireless"), "117");
+    assert_eq!(field("battery"), "3076");
+    assert_eq!(field("doorsensor"), "1");
+    assert_eq!(field("status"), "true");
+    assert_eq!(field("changed"), "false");
+    assert_eq!(field("hardware_id"), "81000038");
+    assert_eq!(field("lqi_dbm"), "-57.55");
+    assert_eq!(field("timestamp"), notify.timestamp().to_string());
+    assert_eq!(field("di1"), "true");
+    assert_eq!(field("di4"), "false");
+    assert_eq!(field("di4_changed"), "false");
+    assert_eq!(field("ad1_mv"), "");
+    assert_eq!(field("ad4_mv"), "");
+}
+
+#[test]
+fn test_web_backend_json() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let (headers, body) = capture_request(
+        &["{url}", "--payload-format", "json"],
+        |b| WebBackend::new_from_backend(b).unwrap(),
+        &[notify],
+    );
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert!(headers.contains("content-type: application/json"));
+    assert_eq!(body["hardware_id"], 0x81000038u32);
+    assert_eq!(body["lqi_dbm"], -57.55);
+    assert_eq!(
+        body["di_status"],
+        serde_json::json!([false, false, false, false])
+    );
+}
+
+#[test]
+fn test_web_backend_bearer_auth() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let (headers, _) = capture_request(
+        &["{url}", "--token", "s3cr3t", "--header", "X-Tenant: t1"],
+        |b| WebBackend::new_from_backend(b).unwrap(),
+        &[notify],
+    );
+
+    assert!(headers.contains("authorization: Bearer s3cr3t\r\n"));
+    assert!(headers.contains("x-tenant: t1\r\n"));
+}
+
+#[test]
+fn test_web_backend_tls_files() {
+    use clap::Parser;
+
+    let garbage = std::env::temp_dir().join(format!("ogenki-test-tls-{}", std::process::id()));
+    fs::write(&garbage, "not a certificate").unwrap();
+
+    let garbage = garbage.to_str().unwrap();
+
+    for args in [
+        ["--client-cert", "/nonexistent.pem", "--client-key", garbage],
+        ["--client-cert", garbage, "--client-key", garbage],
+    ] {
+        let backend = crate::cli::Backend::parse_from(
+            ["test", "https://example.com/"].into_iter().chain(args),
+        );
+
+        assert!(WebBackend::new_from_backend(&backend).is_err());
     }
+
+    fs::remove_file(garbage).unwrap();
+}
+
+#[test]
+fn test_slack_backend() {
+    // No DI change, so only the second frame is posted
+    let frames = [
+        ":7881150175810000380026C9000C04220100FFFFFFFFFFA6",
+        ":7881150175810000380026C9000C04220101FFFFFFFFFFA5",
+    ]
+    .map(|s| StatusNotify::decode_str(s).unwrap());
+
+    let (_, body) = capture_request(
+        &["--slack-webhook-url", "{url}"],
+        SlackBackend::new_from_backend,
+        &frames,
+    );
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    assert_eq!(body["blocks"][0]["text"]["text"], "81000038");
 }
@@ -0,0 +1,77 @@
+use std::net::SocketAddr;
+
+use anyhow::Result;
+use axum::Router;
+
+use crate::health::Health;
+use crate::metrics::Metrics;
+
+/// Serve `/metrics` and `/healthz`, whichever are given, on `addr` from a
+/// background task. Returns the bound address.
+pub async fn serve(
+    addr: SocketAddr,
+    metrics: Option<&'static Metrics>,
+    health: Option<&'static Health>,
+) -> Result<SocketAddr> {
+    let mut app = Router::new();
+
+    if let Some(metrics) = metrics {
+        app = app.merge(metrics.router());
+    }
+
+    if let Some(health) = health {
+        app = app.merge(health.router());
+    }
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            eprintln!("{e}");
+        }
+    });
+
+    Ok(addr)
+}
+
+#[test]
+fn test_serve() {
+    use std::time::{Duration, Instant};
+
+    use twelite_serial::StatusNotify;
+
+    let metrics = &*Box::leak(Box::new(Metrics::new()));
+    let health = &*Box::leak(Box::new(Health::new(Duration::from_secs(60))));
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    metrics.update(&notify);
+    health.update(&notify, Instant::now());
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    runtime.block_on(async {
+        let addr = serve("127.0.0.1:0".parse().unwrap(), Some(metrics), Some(health))
+            .await
+            .unwrap();
+
+        let get = |path: &str| reqwest::get(format!("http://{addr}{path}"));
+
+        let response = get("/metrics").await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("ogenki_lqi"));
+
+        let response = get("/healthz").await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let body: serde_json::Value =
+            serde_json::from_slice(&response.bytes().await.unwrap()).unwrap();
+        assert_eq!(body["devices"][0]["hardware_id"], "81000038");
+
+        assert_eq!(get("/").await.unwrap().status(), 404);
+    });
+}
@@ -0,0 +1,230 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use twelite_serial::{StatusNotify, PACKET_BINARY_LENGTH};
+
+use crate::sender::Backend;
+
+/// Frames the web backend could not deliver, persisted as raw 24 byte records
+/// in `<dir>/web.spool`, oldest first.
+///
+/// Frames the server rejected with 4xx on replay are moved to `<dir>/web.rejected`
+/// in the same format, so they are kept for inspection but never retried.
+/// Both files are capped at `max_bytes`. File access runs on the blocking thread pool.
+pub struct Spool {
+    path: PathBuf,
+    rejected_path: PathBuf,
+    max_bytes: u64,
+    lock: Mutex<()>,
+}
+
+fn to_records(frames: &[StatusNotify]) -> Vec<u8> {
+    frames.iter().flat_map(|f| *f.as_bytes()).collect()
+}
+
+/// A 4xx from the web backend, which replaying again would not fix.
+fn is_rejected(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        .is_some_and(|s| s.is_client_error())
+}
+
+impl Spool {
+    pub fn new(dir: &Path, max_bytes: u64) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        Ok(Self {
+            path: dir.join("web.spool"),
+            rejected_path: dir.join("web.rejected"),
+            max_bytes,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn read(path: &Path) -> Result<Vec<u8>> {
+        match fs::read(path) {
+            Ok(v) => Ok(v),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write `buf` to `path`, keeping only the newest records which fit in `max_bytes`.
+    fn truncate(&self, path: &Path, buf: &[u8]) -> Result<()> {
+        let max = self.max_bytes as usize / PACKET_BINARY_LENGTH * PACKET_BINARY_LENGTH;
+        let buf = &buf[..buf.len() / PACKET_BINARY_LENGTH * PACKET_BINARY_LENGTH];
+
+        if buf.len() <= max {
+            return Ok(fs::write(path, buf)?);
+        }
+
+        let dropped = (buf.len() - max) / PACKET_BINARY_LENGTH;
+        eprintln!(
+            "{} is full, dropping {dropped} oldest frames",
+            path.display()
+        );
+
+        Ok(fs::write(path, &buf[buf.len() - max..])?)
+    }
+
+    /// Append `records` to `path`. The file is only read back if it would exceed `max_bytes`.
+    fn append_blocking(&self, path: &Path, records: &[u8]) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+
+        let len = match fs::metadata(path) {
+            Ok(m) => m.len(),
+            Err(e) if e.kind() == ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+
+        if len + records.len() as u64 <= self.max_bytes {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+            return Ok(file.write_all(records)?);
+        }
+
+        let mut buf = Self::read(path)?;
+        buf.extend_from_slice(records);
+        self.truncate(path, &buf)
+    }
+
+    /// Take all records, oldest first.
+    fn drain_blocking(&self) -> Result<Vec<StatusNotify>> {
+        let _lock = self.lock.lock().unwrap();
+
+        let frames = Self::read(&self.path)?
+            .chunks_exact(PACKET_BINARY_LENGTH)
+            .map(|c| StatusNotify::from_bytes(c.try_into().unwrap()))
+            .collect();
+
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(frames),
+        }
+    }
+
+    /// Put back `records` which could not be replayed, ahead of any frames
+    /// spooled in the meantime.
+    fn restore_blocking(&self, mut records: Vec<u8>) -> Result<()> {
+        let _lock = self.lock.lock().unwrap();
+
+        records.extend(Self::read(&self.path)?);
+        self.truncate(&self.path, &records)
+    }
+
+    /// Append `frames` to the spool, dropping the oldest beyond `max_bytes`.
+    pub async fn push(self: &Arc<Self>, frames: &[StatusNotify]) -> Result<()> {
+        let (spool, records) = (self.clone(), to_records(frames));
+
+        tokio::task::spawn_blocking(move || spool.append_blocking(&spool.path, &records)).await?
+    }
+
+    /// Send spooled frames in order, stopping at the first failure.
+    /// Frames the backend rejects with 4xx are moved to the rejected file and skipped.
+    pub async fn replay(self: &Arc<Self>, backend: &(dyn Backend + Sync)) -> Result<()> {
+        let spool = self.clone();
+        let frames = tokio::task::spawn_blocking(move || spool.drain_blocking()).await??;
+
+        if !frames.is_empty() {
+            eprintln!("Replaying {} spooled frames", frames.len());
+        }
+
+        for (n, notify) in frames.iter().enumerate() {
+            match backend.send(notify).await {
+                Ok(()) => {}
+                Err(e) if is_rejected(&e) => {
+                    eprintln!("{e}, moving to {}", self.rejected_path.display());
+
+                    let (spool, records) = (self.clone(), to_records(&[*notify]));
+                    tokio::task::spawn_blocking(move || {
+                        spool.append_blocking(&spool.rejected_path, &records)
+                    })
+                    .await??;
+                }
+                Err(e) => {
+                    eprintln!("{e}");
+
+                    let (spool, records) = (self.clone(), to_records(&frames[n..]));
+                    return tokio::task::spawn_blocking(move || spool.restore_blocking(records))
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_spool() {
+    use crate::sender::BoxFuture;
+
+    struct FailAfter(Mutex<usize>);
+
+    impl Backend for FailAfter {
+        fn send<'a>(&'a self, _: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                let mut left = self.0.lock().unwrap();
+                anyhow::ensure!(*left > 0, "down");
+                *left -= 1;
+                Ok(())
+            })
+        }
+    }
+
+    /// Rejects packet id 1 with 400 Bad Request, accepts the rest.
+    struct RejectOne;
+
+    impl Backend for RejectOne {
+        fn send<'a>(&'a self, notify: &'a StatusNotify) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                let status = if notify.packet_id() == 1 { 400 } else { 200 };
+                let response = axum::http::Response::builder()
+                    .status(status)
+                    .body("")
+                    .unwrap();
+
+                reqwest::Response::from(response).error_for_status()?;
+                Ok(())
+            })
+        }
+    }
+
+    let dir = std::env::temp_dir().join(format!("ogenki-test-spool-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    let frames: Vec<_> = (0..4).map(|n| crate::sender::sample_with(2, n)).collect();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let spool = Arc::new(Spool::new(&dir, 3 * PACKET_BINARY_LENGTH as u64 + 1).unwrap());
+    runtime.block_on(spool.push(&frames)).unwrap();
+    assert_eq!(fs::metadata(&spool.path).unwrap().len(), 3 * 24);
+
+    runtime
+        .block_on(spool.replay(&FailAfter(Mutex::new(1))))
+        .unwrap();
+    assert_eq!(spool.drain_blocking().unwrap(), frames[2..]);
+
+    runtime.block_on(spool.push(&frames)).unwrap();
+    runtime
+        .block_on(spool.replay(&FailAfter(Mutex::new(3))))
+        .unwrap();
+    assert!(!spool.path.exists());
+
+    // A 4xx is not put back, but moved to the rejected file
+    runtime.block_on(spool.push(&frames[..3])).unwrap();
+    runtime.block_on(spool.replay(&RejectOne)).unwrap();
+    assert!(!spool.path.exists());
+    assert_eq!(
+        fs::read(&spool.rejected_path).unwrap(),
+        frames[1].as_bytes()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,135 @@
+use core::fmt::Write;
+use core::ops::Range;
+use alloc::format;
+use alloc::string::String;
+
+use crate::StatusNotify;
+
+/// Byte range and label of each field, named after its accessor.
+const FIELDS: [(Range<usize>, &str); 16] = [
+    (0..1, "source_device_id"),
+    (1..2, "command"),
+    (2..3, "packet_id"),
+    (3..4, "protocol_version"),
+    (4..5, "lqi"),
+    (5..9, "hardware_id"),
+    (9..10, "dest_device_id"),
+    (10..12, "timestamp"),
+    (12..13, "relay_count"),
+    (13..15, "power_voltage_millis"),
+    (15..16, "unused"),
+    (16..17, "di_status"),
+    (17..18, "di_changed"),
+    (18..22, "ad4_value..ad1_value"),
+    (22..23, "ad_fix"),
+    (23..24, "checksum"),
+];
+
+/// Red, green, yellow, blue, magenta and cyan, in turn.
+const COLORS: [u8; 6] = [31, 32, 33, 34, 35, 36];
+
+impl StatusNotify {
+    /// The wire format split into fields, with one labelled line per field
+    /// pointing at its bytes. For learning and debugging the protocol.
+    ///
+    /// Each field and its label share an ANSI color if stdout is a terminal
+    /// and `NO_COLOR` is not set. Otherwise, or without the `std` feature,
+    /// each field is in brackets.
+    /// See [`StatusNotify::to_debug_hex_annotated_with_color`] to choose.
+    pub fn to_debug_hex_annotated(&self) -> String {
+        #[cfg(feature = "std")]
+        let color = {
+            use std::io::IsTerminal;
+
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        };
+
+        #[cfg(not(feature = "std"))]
+        let color = false;
+
+        self.to_debug_hex_annotated_with_color(color)
+    }
+
+    /// [`StatusNotify::to_debug_hex_annotated`] with ANSI colors if `color`,
+    /// and brackets otherwise.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let annotated = notify.to_debug_hex_annotated_with_color(false);
+    /// let mut lines = annotated.lines();
+    ///
+    /// assert_eq!(lines.next(), Some(":[78] [81] [15] [01] [75] [81000038] [00] [26C9] [00] [0C04] [22] [00] [00] [FFFFFFFF] [FF] [A7]"));
+    /// assert_eq!(lines.next(), Some("  ^^ source_device_id"));
+    /// assert_eq!(lines.next(), Some("       ^^ command"));
+    /// ```
+    pub fn to_debug_hex_annotated_with_color(&self, color: bool) -> String {
+        let mut wire = String::from(":");
+        let mut labels = String::new();
+
+        // Display width of `wire`, without escape sequences.
+        let mut width = 1;
+
+        for (n, (range, name)) in FIELDS.into_iter().enumerate() {
+            if n > 0 {
+                wire.push(' ');
+                width += 1;
+            }
+
+            let digits = range.len() * 2;
+            let (open, close) = match color {
+                true => (format!("\x1b[{}m", COLORS[n % COLORS.len()]), "\x1b[0m"),
+                false => ("[".into(), "]"),
+            };
+
+            let column = if color { width } else { width + 1 };
+
+            wire.push_str(&open);
+            for byte in &self.as_bytes()[range] {
+                write!(wire, "{byte:02X}").unwrap();
+            }
+            wire.push_str(close);
+
+            width += digits + if color { 0 } else { 2 };
+
+            match color {
+                true => writeln!(labels, "{:column$}{open}{:^<digits$} {name}{close}", "", ""),
+                false => writeln!(labels, "{:column$}{:^<digits$} {name}", "", ""),
+            }
+            .unwrap();
+        }
+
+        wire.push('\n');
+        wire.push_str(&labels);
+
+        wire
+    }
+}
+
+#[test]
+fn test_to_debug_hex_annotated() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let plain = notify.to_debug_hex_annotated_with_color(false);
+    let lines: std::vec::Vec<_> = plain.lines().collect();
+
+    assert_eq!(lines.len(), 1 + FIELDS.len());
+    assert_eq!(lines[6], format!("{:27}^^^^^^^^ hardware_id", ""));
+    assert_eq!(lines[16], format!("{:93}^^ checksum", ""));
+
+    // Every label points at its field.
+    for (line, (range, _)) in lines[1..].iter().zip(FIELDS) {
+        let column = line.find('^').unwrap();
+        let digits = range.len() * 2;
+        let field = &lines[0][column..column + digits];
+
+        assert_eq!(lines[0].as_bytes()[column - 1], b'[');
+        assert_eq!(field, &"7881150175810000380026C9000C04220000FFFFFFFFFFA7"[range.start * 2..][..digits]);
+    }
+
+    let colored = notify.to_debug_hex_annotated_with_color(true);
+    assert!(colored.starts_with(":\x1b[31m78\x1b[0m \x1b[32m81\x1b[0m"));
+    assert!(colored.contains("\n \x1b[31m^^ source_device_id\x1b[0m\n"));
+    assert!(colored.contains("\n    \x1b[32m^^ command\x1b[0m\n"));
+}
@@ -0,0 +1,117 @@
+use crate::{DecodeError, StatusNotify, PACKET_BINARY_LENGTH};
+
+/// Base64URL (RFC 4648 §5) of [`PACKET_BINARY_LENGTH`] bytes.
+/// 24 bytes are a multiple of 3, so there is never any padding.
+pub const PACKET_BASE64URL_LENGTH: usize = PACKET_BINARY_LENGTH / 3 * 4;
+
+#[cfg(feature = "alloc")]
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn sextet(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'-' => Some(62),
+        b'_' => Some(63),
+        _ => None,
+    }
+}
+
+impl StatusNotify {
+    /// Decode the raw frame from Base64URL, as made by [`StatusNotify::to_base64url`].
+    ///
+    /// WARNING: This method doesn't check the validity.
+    /// See [`StatusNotify::validate`], If you need validator.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let notify = StatusNotify::from_base64url("eIEVAXWBAAA4ACbJAAwEIgAA______-n").unwrap();
+    /// assert_eq!(notify, StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap());
+    /// ```
+    pub fn from_base64url(s: &str) -> Result<Self, DecodeError> {
+        let s = s.as_bytes();
+
+        if s.len() != PACKET_BASE64URL_LENGTH {
+            return Err(DecodeError::InvalidLength {
+                expected: PACKET_BASE64URL_LENGTH,
+                actual: s.len(),
+            });
+        }
+
+        let mut buf = [0u8; PACKET_BINARY_LENGTH];
+
+        for (n, (chunk, out)) in s.chunks_exact(4).zip(buf.chunks_exact_mut(3)).enumerate() {
+            let mut group = 0u32;
+
+            for (i, &c) in chunk.iter().enumerate() {
+                let v = match sextet(c) {
+                    Some(v) => v,
+                    None if !c.is_ascii() => return Err(DecodeError::NonAsciiByte(c)),
+                    None => {
+                        return Err(DecodeError::InvalidCharacter {
+                            byte: c,
+                            index: n * 4 + i,
+                        })
+                    }
+                };
+
+                group = group << 6 | v as u32;
+            }
+
+            out.copy_from_slice(&group.to_be_bytes()[1..]);
+        }
+
+        Ok(Self::from_bytes(buf))
+    }
+
+    /// The raw frame as unpadded Base64URL, safe in URLs, QR codes and NFC tags.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.to_base64url(), "eIEVAXWBAAA4ACbJAAwEIgAA______-n");
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_base64url(&self) -> alloc::string::String {
+        self.as_bytes()
+            .chunks_exact(3)
+            .flat_map(|chunk| {
+                let group = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+                [18, 12, 6, 0].map(|shift| ALPHABET[(group >> shift & 0x3F) as usize] as char)
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_base64url() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        StatusNotify::from_base64url("eIEVAXWBAAA4ACbJAAwEIgAA______-n"),
+        Ok(notify),
+    );
+    assert_eq!(
+        StatusNotify::from_base64url("eIEVAXWBAAA4ACbJAAwEIgAA"),
+        Err(DecodeError::InvalidLength { expected: 32, actual: 24 }),
+    );
+    assert_eq!(
+        StatusNotify::from_base64url("eIEVAXWBAAA4ACbJAAwEIgAA/_____-n"),
+        Err(DecodeError::InvalidCharacter { byte: b'/', index: 24 }),
+    );
+    assert_eq!(
+        StatusNotify::from_base64url("eIEVAXWBAAA4ACbJAAwEIgAA______\u{e9}"),
+        Err(DecodeError::NonAsciiByte(0xC3)),
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_to_base64url_roundtrip() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220F08FFFFFFFFFF90").unwrap();
+
+    assert_eq!(StatusNotify::from_base64url(&notify.to_base64url()), Ok(notify));
+}
@@ -0,0 +1,92 @@
+use crate::StatusNotify;
+
+/// Change of [`StatusNotify::power_voltage_millis`] over some [`StatusNotify::timestamp`] ticks.
+/// See [`StatusNotify::battery_charge_ticks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BatteryTrend {
+    /// Negative while discharging.
+    pub delta_mv: i16,
+    pub ticks: u16,
+}
+
+impl BatteryTrend {
+    /// Discharge rate in mV per tick. `None` if `ticks` is 0.
+    pub fn mv_per_tick(&self) -> Option<f32> {
+        (self.ticks != 0).then(|| self.delta_mv as f32 / self.ticks as f32)
+    }
+
+    /// Ticks until `current_mv` falls to `cutoff_mv`, assuming a linear discharge.
+    ///
+    /// Returns [`u32::MAX`] if the voltage is not falling.
+    ///
+    /// ```
+    /// # use twelite_serial::BatteryTrend;
+    /// let trend = BatteryTrend { delta_mv: -2, ticks: 640 };
+    /// assert_eq!(trend.estimated_remaining_ticks(3000, 2700), 96_000);
+    /// assert_eq!(trend.estimated_remaining_ticks(2600, 2700), 0);
+    /// ```
+    pub fn estimated_remaining_ticks(&self, current_mv: u16, cutoff_mv: u16) -> u32 {
+        if self.delta_mv >= 0 || self.ticks == 0 {
+            return u32::MAX;
+        }
+
+        let remaining_mv = current_mv.saturating_sub(cutoff_mv) as u64;
+        let ticks = remaining_mv * self.ticks as u64 / self.delta_mv.unsigned_abs() as u64;
+
+        ticks.try_into().unwrap_or(u32::MAX)
+    }
+}
+
+impl StatusNotify {
+    /// Voltage trend from `prev` to `self`, which must be from the same device.
+    ///
+    /// One tick is `tick_interval` [`StatusNotify::timestamp`] counts, e.g.
+    /// [`StatusNotify::timestamp_hz`] for ticks of one second.
+    /// Returns `None` if `tick_interval` is 0 or less than one tick has passed.
+    ///
+    /// The timestamp is taken with wrapping, so `prev` should be less than one
+    /// [`StatusNotify::timestamp`] period (about 1024 seconds) older.
+    ///
+    /// ```
+    /// # use twelite_serial::{BatteryTrend, StatusNotify};
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.battery_charge_ticks(&notify, 1), None);
+    /// ```
+    pub fn battery_charge_ticks(&self, prev: &StatusNotify, tick_interval: u16) -> Option<BatteryTrend> {
+        let ticks = self.timestamp().wrapping_sub(prev.timestamp()).checked_div(tick_interval)?;
+
+        if ticks == 0 {
+            return None;
+        }
+
+        let delta_mv = self.power_voltage_millis() as i32 - prev.power_voltage_millis() as i32;
+
+        Some(BatteryTrend {
+            delta_mv: delta_mv.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            ticks,
+        })
+    }
+}
+
+#[test]
+fn test_battery_charge_ticks() {
+    // timestamp = 0xFFC0, 3076mV
+    let prev =
+        StatusNotify::decode_str(":78811501758100003800FFC0000C04220000FFFFFFFFFFD7").unwrap();
+
+    // timestamp = 0x0040, 3072mV
+    let next =
+        StatusNotify::decode_str(":788115017581000038000040000C00220000FFFFFFFFFF5A").unwrap();
+
+    let trend = next.battery_charge_ticks(&prev, 1).unwrap();
+
+    assert_eq!(trend, BatteryTrend { delta_mv: -4, ticks: 0x80 });
+    assert_eq!(trend.mv_per_tick(), Some(-4.0 / 128.0));
+    assert_eq!(trend.estimated_remaining_ticks(3072, 2700), 372 * 32);
+    assert_eq!(prev.battery_charge_ticks(&next, 1).unwrap().estimated_remaining_ticks(3076, 2700), u32::MAX);
+
+    // 0x80 counts at 64Hz
+    assert_eq!(next.battery_charge_ticks(&prev, StatusNotify::timestamp_hz()), Some(BatteryTrend { delta_mv: -4, ticks: 2 }));
+    assert_eq!(next.battery_charge_ticks(&prev, 0x81), None);
+    assert_eq!(next.battery_charge_ticks(&prev, 0), None);
+}
@@ -0,0 +1,97 @@
+use core::fmt;
+
+use crate::PACKET_BINARY_LENGTH;
+
+/// Named access to the raw bytes of [`crate::StatusNotify`].
+///
+/// Every field borrows from the underlying buffer, nothing is copied.
+/// See [`crate::StatusNotify::bytes_view`].
+#[derive(Debug)]
+pub struct StatusNotifyBytesView<'a> {
+    pub source_device_id_raw: &'a u8,
+    pub command_raw: &'a u8,
+    pub packet_id_raw: &'a u8,
+    pub protocol_version_raw: &'a u8,
+    pub lqi_raw: &'a u8,
+    pub hardware_id_raw: &'a [u8; 4],
+    pub dest_device_id_raw: &'a u8,
+    pub timestamp_raw: &'a [u8; 2],
+    pub relay_count_raw: &'a u8,
+    pub power_voltage_raw: &'a [u8; 2],
+
+    /// Not used by the reference.
+    pub unused_raw: &'a u8,
+
+    pub di_status_raw: &'a u8,
+    pub di_changed_raw: &'a u8,
+
+    /// In wire order, AD4 first.
+    pub ad_value_raw: &'a [u8; 4],
+
+    pub ad_fix_raw: &'a u8,
+    pub checksum_raw: &'a u8,
+}
+
+impl<'a> StatusNotifyBytesView<'a> {
+    pub(crate) fn new(buf: &'a [u8; PACKET_BINARY_LENGTH]) -> Self {
+        Self {
+            source_device_id_raw: &buf[0],
+            command_raw: &buf[1],
+            packet_id_raw: &buf[2],
+            protocol_version_raw: &buf[3],
+            lqi_raw: &buf[4],
+            hardware_id_raw: buf[5..9].try_into().unwrap(),
+            dest_device_id_raw: &buf[9],
+            timestamp_raw: buf[10..12].try_into().unwrap(),
+            relay_count_raw: &buf[12],
+            power_voltage_raw: buf[13..15].try_into().unwrap(),
+            unused_raw: &buf[15],
+            di_status_raw: &buf[16],
+            di_changed_raw: &buf[17],
+            ad_value_raw: buf[18..22].try_into().unwrap(),
+            ad_fix_raw: &buf[22],
+            checksum_raw: &buf[23],
+        }
+    }
+}
+
+fn write_field(f: &mut fmt::Formatter<'_>, offset: usize, name: &str, bytes: &[u8]) -> fmt::Result {
+    if bytes.len() == 1 {
+        write!(f, "[{offset:02}]     {name}: ")?;
+    } else {
+        write!(f, "[{offset:02}..{:02}] {name}: ", offset + bytes.len())?;
+    }
+
+    for b in bytes {
+        write!(f, "{b:02X}")?;
+    }
+
+    writeln!(f)
+}
+
+/// One field per line, prefixed by its byte offset.
+///
+/// ```txt
+/// [00]     source_device_id: 78
+/// [05..09] hardware_id: 81000038
+/// ```
+impl fmt::Display for StatusNotifyBytesView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_field(f, 0, "source_device_id", &[*self.source_device_id_raw])?;
+        write_field(f, 1, "command", &[*self.command_raw])?;
+        write_field(f, 2, "packet_id", &[*self.packet_id_raw])?;
+        write_field(f, 3, "protocol_version", &[*self.protocol_version_raw])?;
+        write_field(f, 4, "lqi", &[*self.lqi_raw])?;
+        write_field(f, 5, "hardware_id", self.hardware_id_raw)?;
+        write_field(f, 9, "dest_device_id", &[*self.dest_device_id_raw])?;
+        write_field(f, 10, "timestamp", self.timestamp_raw)?;
+        write_field(f, 12, "relay_count", &[*self.relay_count_raw])?;
+        write_field(f, 13, "power_voltage", self.power_voltage_raw)?;
+        write_field(f, 15, "unused", &[*self.unused_raw])?;
+        write_field(f, 16, "di_status", &[*self.di_status_raw])?;
+        write_field(f, 17, "di_changed", &[*self.di_changed_raw])?;
+        write_field(f, 18, "ad_value", self.ad_value_raw)?;
+        write_field(f, 22, "ad_fix", &[*self.ad_fix_raw])?;
+        write_field(f, 23, "checksum", &[*self.checksum_raw])
+    }
+}
@@ -0,0 +1,105 @@
+use crate::StatusNotify;
+
+/// Owned snapshot of the decoded fields of a [`StatusNotify`].
+///
+/// Plain data with no reference to the raw buffer. See [`StatusNotify::decoded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodedStatus {
+    pub source_device_id: u8,
+    pub hardware_id: u32,
+    pub timestamp: u16,
+    pub lqi: u8,
+    pub power_voltage_millis: u16,
+    pub di: [bool; 4],
+    pub di_changed: [bool; 4],
+    pub ad_millis: [u16; 4],
+    pub checksum: u8,
+}
+
+impl StatusNotify {
+    /// Copy every decoded field into a [`DecodedStatus`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let decoded = notify.decoded();
+    /// assert_eq!(decoded.hardware_id, 0x81000038);
+    /// assert_eq!(decoded.power_voltage_millis, 3076);
+    /// ```
+    pub fn decoded(&self) -> DecodedStatus {
+        DecodedStatus {
+            source_device_id: self.source_device_id(),
+            hardware_id: self.hardware_id(),
+            timestamp: self.timestamp(),
+            lqi: self.lqi(),
+            power_voltage_millis: self.power_voltage_millis(),
+            di: self.di_statuses(),
+            di_changed: self.di_changes(),
+            ad_millis: self.ad_voltages_millis(),
+            checksum: self.checksum(),
+        }
+    }
+}
+
+impl From<StatusNotify> for DecodedStatus {
+    fn from(value: StatusNotify) -> Self {
+        value.decoded()
+    }
+}
+
+/// Uses the same names as the [`StatusNotify`] serialization, such as
+/// `di_status` for [`DecodedStatus::di`], so both produce one JSON schema.
+#[cfg(feature = "serde")]
+impl serde::Serialize for DecodedStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("DecodedStatus", 9)?;
+        s.serialize_field("source_device_id", &self.source_device_id)?;
+        s.serialize_field("hardware_id", &self.hardware_id)?;
+        s.serialize_field("timestamp", &self.timestamp)?;
+        s.serialize_field("lqi", &self.lqi)?;
+        s.serialize_field("power_voltage_millis", &self.power_voltage_millis)?;
+        s.serialize_field("di_status", &self.di)?;
+        s.serialize_field("di_changed", &self.di_changed)?;
+        s.serialize_field("ad_voltage_millis", &self.ad_millis)?;
+        s.serialize_field("checksum", &self.checksum)?;
+        s.end()
+    }
+}
+
+#[test]
+fn test_decoded() {
+    // DI1 and DI3 set, DI3 changed, AD1 = 0x10, AD2 = 0x20, AD3 = 0x30, AD4 = 0xFF
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220504FF302010E4FF").unwrap();
+
+    let decoded = notify.decoded();
+
+    assert_eq!(decoded.source_device_id, notify.source_device_id());
+    assert_eq!(decoded.hardware_id, notify.hardware_id());
+    assert_eq!(decoded.timestamp, notify.timestamp());
+    assert_eq!(decoded.lqi, notify.lqi());
+    assert_eq!(decoded.power_voltage_millis, notify.power_voltage_millis());
+    assert_eq!(decoded.di, notify.di_statuses());
+    assert_eq!(decoded.di_changed, notify.di_changes());
+    assert_eq!(decoded.ad_millis, notify.ad_voltages_millis());
+    assert_eq!(decoded.checksum, notify.checksum());
+
+    assert_eq!(DecodedStatus::from(notify), decoded);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220504FF302010E4FF").unwrap();
+
+    let decoded = serde_json::to_value(notify.decoded()).unwrap();
+    let full = serde_json::to_value(notify).unwrap();
+
+    // Every field has the name and value it has in the StatusNotify schema
+    for (key, value) in decoded.as_object().unwrap() {
+        assert_eq!(full[key], *value, "{key}");
+    }
+}
@@ -0,0 +1,87 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::StatusNotify;
+
+impl StatusNotify {
+    /// Describe DI edges since `prev`, e.g. `["DI1 opened", "DI3 closed"]`.
+    ///
+    /// A set bit is `opened`, a cleared bit is `closed`.
+    /// `None` entries in `names` fall back to `DI1`..`DI4`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let prev = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let next = StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+    ///
+    /// assert_eq!(next.describe_di_changes(&prev, &[Some("front_door"), None, None, None]), ["front_door opened"]);
+    /// assert_eq!(prev.describe_di_changes(&next, &[None; 4]), ["DI1 closed"]);
+    /// ```
+    pub fn describe_di_changes(&self, prev: &Self, names: &[Option<&str>; 4]) -> Vec<String> {
+        let edges = self.di_statuses().into_iter().zip(prev.di_statuses());
+
+        names
+            .iter()
+            .zip(edges)
+            .enumerate()
+            .filter(|(_, (_, (now, before)))| now != before)
+            .map(|(n, (name, (now, _)))| {
+                let edge = if now { "opened" } else { "closed" };
+
+                match name {
+                    Some(name) => format!("{name} {edge}"),
+                    None => format!("DI{} {edge}", n + 1),
+                }
+            })
+            .collect()
+    }
+
+    /// [`StatusNotify::di_status`] as `0b` and 4 bits, DI4 first.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220500FFFFFFFFFFA2").unwrap();
+    /// assert_eq!(notify.di_status_to_binary_string(), "0b0101");
+    /// ```
+    pub fn di_status_to_binary_string(&self) -> String {
+        format!("{:#06b}", self.di_status() & 0x0F)
+    }
+
+    /// [`StatusNotify::di_changed`] as `0b` and 4 bits, DI4 first.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.di_changed_to_binary_string(), "0b0000");
+    /// ```
+    pub fn di_changed_to_binary_string(&self) -> String {
+        format!("{:#06b}", self.di_changed() & 0x0F)
+    }
+}
+
+#[test]
+fn test_describe_di_changes() {
+    // DI1 and DI3 set
+    let prev =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220500FFFFFFFFFFA2").unwrap();
+
+    // DI1 and DI2 set
+    let next =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220300FFFFFFFFFFA4").unwrap();
+
+    assert_eq!(
+        next.describe_di_changes(&prev, &[None, Some("window"), None, None]),
+        ["window opened", "DI3 closed"],
+    );
+    assert!(next.describe_di_changes(&next, &[None; 4]).is_empty());
+}
+
+#[test]
+fn test_di_binary_string() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220F08FFFFFFFFFF90").unwrap();
+
+    assert_eq!(notify.di_status_to_binary_string(), "0b1111");
+    assert_eq!(notify.di_changed_to_binary_string(), "0b1000");
+}
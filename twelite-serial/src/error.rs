@@ -3,21 +3,35 @@ use core::fmt;
 /// A decode error structure.
 #[derive(Debug, Eq, PartialEq)]
 pub enum DecodeError {
-    InvalidLength(usize),
-    InvalidCharacter(u8),
+    InvalidLength { expected: usize, actual: usize },
+    /// `index` is the position within the original input, including the leading `:`.
+    InvalidCharacter { byte: u8, index: usize },
+
+    /// The byte has the high bit set. Usually line noise rather than a protocol mismatch.
+    NonAsciiByte(u8),
+
+    /// The iterator ran out. See [`crate::StatusNotify::decode_from_iterator`].
+    UnexpectedEof { expected: usize, actual: usize },
 }
 
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::InvalidLength(len) =>
-                write!(f, "Unexpected length: {len}"),
-            Self::InvalidCharacter(c) => 
-                write!(f, "Hit to invalid character {c} when decode"),
+            Self::InvalidLength { expected, actual } =>
+                write!(f, "Unexpected length: expected {expected}, got {actual}"),
+            Self::InvalidCharacter { byte, index } =>
+                write!(f, "Hit to invalid character 0x{byte:02X} at position {index} when decode"),
+            Self::NonAsciiByte(c) =>
+                write!(f, "Hit to non-ASCII byte 0x{c:02X} when decode"),
+            Self::UnexpectedEof { expected, actual } =>
+                write!(f, "Unexpected EOF: expected {expected} bytes, got {actual}"),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
 /// A validate error structure.
 /// This is only emmits when explicit calls validate functions.
 #[derive(Debug, Eq, PartialEq)]
@@ -49,3 +63,91 @@ impl fmt::Display for ValidateError {
         }
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidateError {}
+
+/// A reserved bit is set.
+/// See [`crate::StatusNotify::validate_all_reserved_bits`].
+#[derive(Debug, Eq, PartialEq)]
+pub struct ReservedBitError {
+    pub field: &'static str,
+
+    /// Position in the binary buffer.
+    pub byte: u8,
+
+    /// Bits which may be set. Anything outside is reserved.
+    pub expected_mask: u8,
+    pub actual: u8,
+}
+
+impl fmt::Display for ReservedBitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Reserved bits of {} (byte {}) must be 0, mask 0x{:02X} but actually 0x{:02X}",
+            self.field, self.byte, self.expected_mask, self.actual,
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReservedBitError {}
+
+/// See [`crate::StatusNotify::validate_packet_id_sequence`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum PacketIdError {
+    /// `gap - 1` packets were probably lost between the two frames.
+    GapTooLarge { expected: u8, got: u8, gap: u8 },
+}
+
+impl fmt::Display for PacketIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GapTooLarge { expected, got, gap } =>
+                write!(f, "Packet id is expected to be {expected}, but actually {got} (gap {gap})"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PacketIdError {}
+
+/// Either of [`DecodeError`] or [`ValidateError`].
+/// See [`crate::StatusNotify::decode_validated`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum TweliteError {
+    Decode(DecodeError),
+    Validate(ValidateError),
+}
+
+impl fmt::Display for TweliteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Decode(e) => e.fmt(f),
+            Self::Validate(e) => e.fmt(f),
+        }
+    }
+}
+
+impl From<DecodeError> for TweliteError {
+    fn from(e: DecodeError) -> Self {
+        Self::Decode(e)
+    }
+}
+
+impl From<ValidateError> for TweliteError {
+    fn from(e: ValidateError) -> Self {
+        Self::Validate(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TweliteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Decode(e) => Some(e),
+            Self::Validate(e) => Some(e),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use core::fmt;
+
+/// Typed [`crate::StatusNotify::hardware_id`].
+///
+/// Formatted as 8-digit zero-padded hex, like `81000038`.
+/// `{:#}`, `{:#x}` and `{:#X}` add the `0x` prefix, like `0x81000038`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HardwareId(u32);
+
+impl HardwareId {
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for HardwareId {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for HardwareId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for HardwareId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+
+        write!(f, "{:08x}", self.0)
+    }
+}
+
+impl fmt::UpperHex for HardwareId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+
+        write!(f, "{:08X}", self.0)
+    }
+}
+
+#[test]
+fn test() {
+    use std::format;
+    use std::string::ToString;
+
+    assert_eq!(HardwareId(0x38).to_string(), "00000038");
+    assert_eq!(HardwareId(0x81000038).to_string(), "81000038");
+    assert_eq!(format!("{:#}", HardwareId(0x38)), "0x00000038");
+    assert_eq!(format!("{:x}", HardwareId(0xABC)), "00000abc");
+    assert_eq!(format!("{:X}", HardwareId(0xABC)), "00000ABC");
+    assert_eq!(format!("{:#X}", HardwareId(0xABC)), "0x00000ABC");
+}
@@ -0,0 +1,36 @@
+use core::fmt;
+
+use crate::StatusNotify;
+
+/// Formats a logical device ID as `0x78` without allocating.
+///
+/// See [`StatusNotify::source_device_id_display`], and [`crate::HardwareId`]
+/// with `{:#}` for the hardware ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceDeviceIdDisplay(pub u8);
+
+impl fmt::Display for SourceDeviceIdDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X}", self.0)
+    }
+}
+
+impl StatusNotify {
+    /// [`StatusNotify::source_device_id`] for use with `{}`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(format!("{}", notify.source_device_id_display()), "0x78");
+    /// ```
+    pub fn source_device_id_display(&self) -> SourceDeviceIdDisplay {
+        SourceDeviceIdDisplay(self.source_device_id())
+    }
+}
+
+#[test]
+fn test() {
+    use std::string::ToString;
+
+    assert_eq!(SourceDeviceIdDisplay(0x0A).to_string(), "0x0A");
+}
@@ -0,0 +1,42 @@
+use alloc::format;
+use alloc::string::ToString;
+
+use serde_json::{Map, Value};
+
+use crate::StatusNotify;
+
+impl StatusNotify {
+    /// DI channels as a JSON object of [`bool`], keyed by `names`.
+    ///
+    /// `None` entries fall back to `DI1`..`DI4`.
+    pub fn di_status_as_json_object(&self, names: &[Option<&str>; 4]) -> Value {
+        let mut object = Map::new();
+
+        for (n, (name, status)) in names.iter().zip(self.di_statuses()).enumerate() {
+            let key = match name {
+                Some(name) => name.to_string(),
+                None => format!("DI{}", n + 1),
+            };
+
+            object.insert(key, Value::Bool(status));
+        }
+
+        Value::Object(object)
+    }
+}
+
+#[test]
+fn test_di_status_as_json_object() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    assert_eq!(
+        notify.di_status_as_json_object(&[Some("front_door"), None, None, None]),
+        serde_json::json!({
+            "front_door": true,
+            "DI2": false,
+            "DI3": false,
+            "DI4": false,
+        }),
+    );
+}
@@ -1,6 +1,59 @@
 #![no_std]
+
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub(crate) mod status_notify;
 pub(crate) mod error;
+pub(crate) mod bytes_view;
+pub(crate) mod hardware_id;
+pub(crate) mod lqi_quality;
+pub(crate) mod decoded_status;
+pub(crate) mod battery_trend;
+pub(crate) mod id_display;
+pub(crate) mod packet_filter;
+pub(crate) mod base64url;
+
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impl;
+
+#[cfg(feature = "serde")]
+pub(crate) mod newtype_serde;
+
+#[cfg(feature = "json")]
+pub(crate) mod json;
+
+#[cfg(feature = "slack")]
+pub(crate) mod slack;
+
+#[cfg(feature = "matter")]
+pub(crate) mod matter;
+
+#[cfg(feature = "std")]
+pub(crate) mod sensor_event;
+
+#[cfg(feature = "alloc")]
+pub(crate) mod di_description;
+
+#[cfg(feature = "alloc")]
+pub(crate) mod annotated_hex;
 
 pub use status_notify::*;
 pub use error::*;
+pub use bytes_view::*;
+pub use hardware_id::*;
+pub use lqi_quality::*;
+pub use decoded_status::*;
+pub use battery_trend::*;
+pub use id_display::*;
+pub use packet_filter::*;
+pub use base64url::*;
+
+#[cfg(feature = "serde")]
+pub use newtype_serde::*;
+
+#[cfg(feature = "std")]
+pub use sensor_event::*;
@@ -0,0 +1,50 @@
+use core::fmt;
+
+/// Rough LQI bands from the reference. See [`crate::StatusNotify::lqi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LqiQuality {
+    /// Less than 50 (less than -80dBm).
+    Bad,
+
+    /// 50 to 99.
+    Poor,
+
+    /// 100 to 149.
+    Good,
+
+    /// 150 or more, near the antenna.
+    Near,
+}
+
+impl LqiQuality {
+    pub fn from_lqi(lqi: u8) -> Self {
+        match lqi {
+            0..=49 => Self::Bad,
+            50..=99 => Self::Poor,
+            100..=149 => Self::Good,
+            150..=255 => Self::Near,
+        }
+    }
+}
+
+impl fmt::Display for LqiQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bad => write!(f, "Bad"),
+            Self::Poor => write!(f, "Poor"),
+            Self::Good => write!(f, "Good"),
+            Self::Near => write!(f, "Near"),
+        }
+    }
+}
+
+#[test]
+fn test() {
+    assert_eq!(LqiQuality::from_lqi(0), LqiQuality::Bad);
+    assert_eq!(LqiQuality::from_lqi(49), LqiQuality::Bad);
+    assert_eq!(LqiQuality::from_lqi(50), LqiQuality::Poor);
+    assert_eq!(LqiQuality::from_lqi(100), LqiQuality::Good);
+    assert_eq!(LqiQuality::from_lqi(149), LqiQuality::Good);
+    assert_eq!(LqiQuality::from_lqi(150), LqiQuality::Near);
+    assert_eq!(LqiQuality::from_lqi(255), LqiQuality::Near);
+}
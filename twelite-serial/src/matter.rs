@@ -0,0 +1,151 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::StatusNotify;
+
+/// Power Source cluster, reported on endpoint 0.
+const CLUSTER_POWER_SOURCE: u32 = 0x002F;
+/// `BatVoltage` of the Power Source cluster, in mV.
+const ATTRIBUTE_BAT_VOLTAGE: u32 = 0x000B;
+
+/// Boolean State cluster, reported on endpoint 1..=4 for DI1..DI4.
+const CLUSTER_BOOLEAN_STATE: u32 = 0x0045;
+/// `StateValue` of the Boolean State cluster.
+const ATTRIBUTE_STATE_VALUE: u32 = 0x0000;
+
+// Control octets with a context-specific tag, and the untagged ones.
+const ANONYMOUS_STRUCTURE: u8 = 0x15;
+const END_OF_CONTAINER: u8 = 0x18;
+const CONTEXT_UINT16: u8 = 0x25;
+const CONTEXT_UINT32: u8 = 0x26;
+const CONTEXT_FALSE: u8 = 0x28;
+const CONTEXT_TRUE: u8 = 0x29;
+const CONTEXT_STRUCTURE: u8 = 0x35;
+const CONTEXT_ARRAY: u8 = 0x36;
+const CONTEXT_LIST: u8 = 0x37;
+
+enum Value {
+    Bool(bool),
+    UInt32(u32),
+}
+
+fn attribute_report(buf: &mut Vec<u8>, data_version: u32, endpoint: u16, cluster: u32, attribute: u32, value: Value) {
+    // AttributeReportIB { AttributeData: AttributeDataIB }
+    buf.extend([ANONYMOUS_STRUCTURE, CONTEXT_STRUCTURE, 1]);
+
+    buf.extend([CONTEXT_UINT32, 0]);
+    buf.extend(data_version.to_le_bytes());
+
+    // AttributePathIB
+    buf.extend([CONTEXT_LIST, 1]);
+    buf.extend([CONTEXT_UINT16, 2]);
+    buf.extend(endpoint.to_le_bytes());
+    buf.extend([CONTEXT_UINT32, 3]);
+    buf.extend(cluster.to_le_bytes());
+    buf.extend([CONTEXT_UINT32, 4]);
+    buf.extend(attribute.to_le_bytes());
+    buf.push(END_OF_CONTAINER);
+
+    match value {
+        Value::Bool(false) => buf.extend([CONTEXT_FALSE, 2]),
+        Value::Bool(true) => buf.extend([CONTEXT_TRUE, 2]),
+        Value::UInt32(v) => {
+            buf.extend([CONTEXT_UINT32, 2]);
+            buf.extend(v.to_le_bytes());
+        }
+    }
+
+    buf.extend([END_OF_CONTAINER, END_OF_CONTAINER]);
+}
+
+impl StatusNotify {
+    /// The Matter TLV of a ReportDataMessage carrying this frame as attribute reports.
+    ///
+    /// - endpoint 0, Power Source `BatVoltage`: [`StatusNotify::power_voltage_millis`]
+    /// - endpoint 1..=4, Boolean State `StateValue`: DI1..DI4, `true` if closed,
+    ///   as a contact sensor reports it.
+    ///
+    /// [`StatusNotify::packet_id`] is used as the data version.
+    /// Only the `AttributeReports` field is present; the message and
+    /// interaction model framing is left to the Matter stack.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let tlv = notify.to_matter_tlv_bytes();
+    /// assert_eq!(tlv[..3], [0x15, 0x36, 0x01]);
+    /// assert_eq!(tlv[tlv.len() - 2..], [0x18, 0x18]);
+    /// ```
+    pub fn to_matter_tlv_bytes(&self) -> Vec<u8> {
+        let data_version = self.packet_id() as u32;
+
+        // ReportDataMessage { AttributeReports: [...] }
+        let mut buf = vec![ANONYMOUS_STRUCTURE, CONTEXT_ARRAY, 1];
+
+        attribute_report(
+            &mut buf,
+            data_version,
+            0,
+            CLUSTER_POWER_SOURCE,
+            ATTRIBUTE_BAT_VOLTAGE,
+            Value::UInt32(self.power_voltage_millis() as u32),
+        );
+
+        for (endpoint, open) in (1..).zip(self.di_statuses()) {
+            attribute_report(
+                &mut buf,
+                data_version,
+                endpoint,
+                CLUSTER_BOOLEAN_STATE,
+                ATTRIBUTE_STATE_VALUE,
+                Value::Bool(!open),
+            );
+        }
+
+        buf.extend([END_OF_CONTAINER, END_OF_CONTAINER]);
+
+        buf
+    }
+}
+
+#[test]
+fn test_to_matter_tlv_bytes() {
+    // DI1 set
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    let tlv = notify.to_matter_tlv_bytes();
+
+    let battery = [
+        0x15, 0x35, 0x01,
+        0x26, 0x00, 0x15, 0x00, 0x00, 0x00,
+        0x37, 0x01,
+        0x25, 0x02, 0x00, 0x00,
+        0x26, 0x03, 0x2F, 0x00, 0x00, 0x00,
+        0x26, 0x04, 0x0B, 0x00, 0x00, 0x00,
+        0x18,
+        0x26, 0x02, 0x04, 0x0C, 0x00, 0x00,
+        0x18, 0x18,
+    ];
+
+    let di1 = [
+        0x15, 0x35, 0x01,
+        0x26, 0x00, 0x15, 0x00, 0x00, 0x00,
+        0x37, 0x01,
+        0x25, 0x02, 0x01, 0x00,
+        0x26, 0x03, 0x45, 0x00, 0x00, 0x00,
+        0x26, 0x04, 0x00, 0x00, 0x00, 0x00,
+        0x18,
+        0x28, 0x02,
+        0x18, 0x18,
+    ];
+
+    assert_eq!(tlv[..3], [0x15, 0x36, 0x01]);
+    assert_eq!(tlv[3..3 + battery.len()], battery);
+    assert_eq!(tlv[3 + battery.len()..3 + battery.len() + di1.len()], di1);
+
+    // DI2 is closed
+    assert_eq!(tlv[3 + battery.len() + di1.len() * 2 - 4], 0x29);
+
+    assert_eq!(tlv.len(), 3 + battery.len() + di1.len() * 4 + 2);
+}
@@ -0,0 +1,74 @@
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use crate::StatusNotify;
+
+/// Serializes as a newtype struct around an 18-element tuple of the raw
+/// fields in wire order, less the unused byte 15, a compact alternative to the map-based
+/// [`Serialize`] of [`StatusNotify`] for formats like MessagePack or CBOR.
+///
+/// See [`StatusNotify::to_newtype_struct`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusNotifyNewtype(pub StatusNotify);
+
+struct Fields<'a>(&'a [u8; crate::PACKET_BINARY_LENGTH]);
+
+impl Serialize for Fields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let b = self.0;
+        let mut t = serializer.serialize_tuple(18)?;
+
+        // source id, command, packet id, protocol, lqi
+        for v in &b[..5] {
+            t.serialize_element(v)?;
+        }
+
+        t.serialize_element(&u32::from_be_bytes([b[5], b[6], b[7], b[8]]))?;
+        t.serialize_element(&b[9])?;
+        t.serialize_element(&u16::from_be_bytes([b[10], b[11]]))?;
+        t.serialize_element(&b[12])?;
+        t.serialize_element(&u16::from_be_bytes([b[13], b[14]]))?;
+
+        // DI status, DI changed, AD4..AD1, AD fix, checksum; the unused byte 15 is left out
+        for v in &b[16..] {
+            t.serialize_element(v)?;
+        }
+
+        t.end()
+    }
+}
+
+impl Serialize for StatusNotifyNewtype {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("StatusNotify", &Fields(self.0.as_bytes()))
+    }
+}
+
+impl StatusNotify {
+    /// Wrap for compact serialization, see [`StatusNotifyNewtype`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(
+    ///     serde_json::to_string(&notify.to_newtype_struct()).unwrap(),
+    ///     "[120,129,21,1,117,2164260920,0,9929,0,3076,0,0,255,255,255,255,255,167]",
+    /// );
+    /// ```
+    pub fn to_newtype_struct(&self) -> StatusNotifyNewtype {
+        StatusNotifyNewtype(*self)
+    }
+}
+
+#[test]
+fn test_to_newtype_struct() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220100FFFFFFFFFFA6").unwrap();
+
+    let value = serde_json::to_value(notify.to_newtype_struct()).unwrap();
+    let fields = value.as_array().unwrap();
+
+    assert_eq!(fields.len(), 18);
+    assert_eq!(fields[5], 0x81000038u32);
+    assert_eq!(fields[10], 1);
+    assert_eq!(fields[17], 0xA6);
+}
@@ -0,0 +1,74 @@
+use crate::StatusNotify;
+
+/// A predicate over single frames. See [`StatusNotify::matches_filter`].
+///
+/// Implemented for closures, for slices and `Vec`s (every filter must match),
+/// and for `Arc`, so a shared `Arc<dyn PacketFilter + Send + Sync>` or a
+/// `Vec` of them can be used as is.
+pub trait PacketFilter {
+    fn matches(&self, notify: &StatusNotify) -> bool;
+}
+
+impl<F: Fn(&StatusNotify) -> bool> PacketFilter for F {
+    fn matches(&self, notify: &StatusNotify) -> bool {
+        self(notify)
+    }
+}
+
+impl<T: PacketFilter> PacketFilter for [T] {
+    fn matches(&self, notify: &StatusNotify) -> bool {
+        self.iter().all(|f| f.matches(notify))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PacketFilter + ?Sized> PacketFilter for alloc::sync::Arc<T> {
+    fn matches(&self, notify: &StatusNotify) -> bool {
+        (**self).matches(notify)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PacketFilter> PacketFilter for alloc::vec::Vec<T> {
+    fn matches(&self, notify: &StatusNotify) -> bool {
+        self.as_slice().matches(notify)
+    }
+}
+
+impl StatusNotify {
+    /// Evaluate `filter` against this frame, for early exit in a read loop.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let strong = |n: &StatusNotify| n.lqi() > 100;
+    /// assert!(notify.matches_filter(&strong));
+    /// ```
+    pub fn matches_filter<F: PacketFilter + ?Sized>(&self, filter: &F) -> bool {
+        filter.matches(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_matches_filter() {
+    use alloc::sync::Arc;
+    use alloc::vec;
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let filters: vec::Vec<Arc<dyn PacketFilter + Send + Sync>> = vec![
+        Arc::new(|n: &StatusNotify| n.source_device_id() == 0x78),
+        Arc::new(|n: &StatusNotify| n.lqi() > 100),
+    ];
+
+    assert!(notify.matches_filter(&filters));
+
+    let filters = Arc::new(filters);
+    assert!(notify.matches_filter(&*filters));
+
+    let strict: [fn(&StatusNotify) -> bool; 2] = [|n| n.lqi() > 100, |n| n.lqi() > 200];
+    assert!(!notify.matches_filter(&strict[..]));
+    assert!(notify.matches_filter(&strict[..0]));
+}
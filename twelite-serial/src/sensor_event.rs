@@ -0,0 +1,81 @@
+use core::fmt;
+use std::string::String;
+use std::time::SystemTime;
+
+use crate::StatusNotify;
+
+/// A [`StatusNotify`] paired with host-side context.
+/// See [`StatusNotify::to_sensor_event`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorEvent {
+    /// When the host received the packet.
+    pub wall_clock: SystemTime,
+    pub device_name: String,
+    pub status: StatusNotify,
+}
+
+impl StatusNotify {
+    /// Pair with host-side context.
+    pub fn to_sensor_event(&self, wall_clock: SystemTime, device_name: &str) -> SensorEvent {
+        SensorEvent {
+            wall_clock,
+            device_name: device_name.into(),
+            status: *self,
+        }
+    }
+}
+
+impl fmt::Display for SensorEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.device_name, self.status)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SensorEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("SensorEvent", 3)?;
+        s.serialize_field("wall_clock", &self.wall_clock)?;
+        s.serialize_field("device_name", &self.device_name)?;
+        s.serialize_field("status", &self.status)?;
+        s.end()
+    }
+}
+
+#[test]
+fn test() {
+    use std::string::ToString;
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let event = notify.to_sensor_event(SystemTime::UNIX_EPOCH, "front_door");
+
+    assert_eq!(event.status, notify);
+    assert_eq!(
+        event.to_string(),
+        "front_door: -57.55dBm 3076mV is_open: false changed: false",
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize() {
+    use std::time::Duration;
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let event = notify.to_sensor_event(SystemTime::UNIX_EPOCH + Duration::from_millis(1500), "front_door");
+
+    assert_eq!(
+        serde_json::to_value(&event).unwrap(),
+        serde_json::json!({
+            "wall_clock": { "secs_since_epoch": 1, "nanos_since_epoch": 500_000_000 },
+            "device_name": "front_door",
+            "status": serde_json::to_value(notify).unwrap(),
+        }),
+    );
+}
@@ -0,0 +1,245 @@
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::StatusNotify;
+
+/// Fields of the serialized [`StatusNotify`], in order.
+const FIELDS: &[&str] = &[
+    "source_device_id",
+    "hardware_id",
+    "timestamp",
+    "lqi",
+    "lqi_dbm",
+    "power_voltage_millis",
+    "di_status",
+    "di_changed",
+    "ad_voltage_millis",
+    "checksum",
+    "frame",
+];
+
+/// The frame string, as accepted by [`StatusNotify::decode_str`].
+struct Frame<'a>(&'a StatusNotify);
+
+impl fmt::Display for Frame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(":")?;
+
+        for byte in self.0.as_bytes() {
+            write!(f, "{byte:02X}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Frame<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Emits the decoded fields, not the raw buffer, and the frame string as
+/// `frame` so that it can be deserialized back.
+impl Serialize for StatusNotify {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("StatusNotify", FIELDS.len())?;
+
+        s.serialize_field("source_device_id", &self.source_device_id())?;
+        s.serialize_field("hardware_id", &self.hardware_id())?;
+        s.serialize_field("timestamp", &self.timestamp())?;
+        s.serialize_field("lqi", &self.lqi())?;
+        s.serialize_field("lqi_dbm", &self.lqi_dbm())?;
+        s.serialize_field("power_voltage_millis", &self.power_voltage_millis())?;
+        s.serialize_field("di_status", &self.di_statuses())?;
+        s.serialize_field("di_changed", &self.di_changes())?;
+        s.serialize_field("ad_voltage_millis", &self.ad_voltages_millis())?;
+        s.serialize_field("checksum", &self.checksum())?;
+        s.serialize_field("frame", &Frame(self))?;
+
+        s.end()
+    }
+}
+
+struct FrameVisitor;
+
+impl<'de> Visitor<'de> for FrameVisitor {
+    type Value = StatusNotify;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a frame string such as \":7881...A7\" or a serialized StatusNotify")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        StatusNotify::decode_str(v).map_err(E::custom)
+    }
+
+    /// Only `frame` is read, the decoded fields are derived from it.
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut notify = None;
+
+        while let Some(key) = map.next_key::<Field>()? {
+            match key {
+                Field::Frame => notify = Some(map.next_value_seed(FrameSeed)?),
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        notify.ok_or_else(|| de::Error::missing_field("frame"))
+    }
+
+    /// The fields in [`FIELDS`] order, as formats without field names write a struct.
+    /// Each is read with its own type, so that non-self-describing formats
+    /// can skip it, and only `frame` is kept.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        skip::<u8, _>(&mut seq, 0)?;
+        skip::<u32, _>(&mut seq, 1)?;
+        skip::<u16, _>(&mut seq, 2)?;
+        skip::<u8, _>(&mut seq, 3)?;
+        skip::<f32, _>(&mut seq, 4)?;
+        skip::<u16, _>(&mut seq, 5)?;
+        skip::<[bool; 4], _>(&mut seq, 6)?;
+        skip::<[bool; 4], _>(&mut seq, 7)?;
+        skip::<[u16; 4], _>(&mut seq, 8)?;
+        skip::<u8, _>(&mut seq, 9)?;
+
+        seq.next_element_seed(FrameSeed)?
+            .ok_or_else(|| de::Error::invalid_length(10, &self))
+    }
+}
+
+/// Read the `n`th element of a struct written as a sequence, and drop it.
+fn skip<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+    seq: &mut A,
+    n: usize,
+) -> Result<(), A::Error> {
+    match seq.next_element::<T>()? {
+        Some(_) => Ok(()),
+        None => Err(de::Error::invalid_length(n, &FrameVisitor)),
+    }
+}
+
+struct FrameSeed;
+
+impl<'de> de::DeserializeSeed<'de> for FrameSeed {
+    type Value = StatusNotify;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(FrameVisitor)
+    }
+}
+
+enum Field {
+    Frame,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a field name")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(match v {
+                    "frame" => Field::Frame,
+                    _ => Field::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// Expects the frame string, or the serialized form with its `frame` field,
+/// and runs it through [`StatusNotify::decode_str`].
+///
+/// Only human-readable formats such as JSON may give either, as that needs
+/// [`Deserializer::deserialize_any`]. Other formats, which may not describe
+/// their own data, such as postcard or bincode, must give the serialized form.
+///
+/// WARNING: Like [`StatusNotify::decode_str`], this doesn't check the validity.
+impl<'de> Deserialize<'de> for StatusNotify {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_any(FrameVisitor),
+            false => deserializer.deserialize_struct("StatusNotify", FIELDS, FrameVisitor),
+        }
+    }
+}
+
+#[test]
+fn test_serialize() {
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&notify).unwrap(),
+        concat!(
+            r#"{"source_device_id":120,"hardware_id":2164260920,"timestamp":9929,"#,
+            r#""lqi":117,"lqi_dbm":-57.55,"power_voltage_millis":3076,"#,
+            r#""di_status":[false,false,false,false],"di_changed":[false,false,false,false],"#,
+            r#""ad_voltage_millis":[4092,4092,4092,4092],"checksum":167,"#,
+            r#""frame":":7881150175810000380026C9000C04220000FFFFFFFFFFA7"}"#,
+        ),
+    );
+}
+
+#[test]
+fn test_deserialize() {
+    #[derive(serde::Deserialize)]
+    struct Document {
+        frame: StatusNotify,
+    }
+
+    let frame = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+
+    let doc: Document =
+        serde_json::from_str(r#"{"frame": ":7881150175810000380026C9000C04220000FFFFFFFFFFA7"}"#)
+            .unwrap();
+
+    let notify = StatusNotify::decode_str(frame).unwrap();
+
+    assert_eq!(doc.frame, notify);
+    assert_eq!(
+        serde_json::to_string(&doc.frame).unwrap(),
+        serde_json::to_string(&notify).unwrap(),
+    );
+
+    let err = serde_json::from_str::<StatusNotify>(r#"":7881""#).unwrap_err();
+    assert!(err.is_data());
+}
+
+#[test]
+fn test_round_trip() {
+    use std::string::ToString;
+
+    // relay count, packet id and AD fix are not among the decoded fields
+    let notify =
+        StatusNotify::decode_str(":7802160175810000380026C9030C04220303FFFFFFFF0318").unwrap();
+
+    let json = serde_json::to_string(&notify).unwrap();
+    assert_eq!(serde_json::from_str::<StatusNotify>(&json).unwrap(), notify);
+
+    let err = serde_json::from_str::<StatusNotify>(r#"{"checksum":167}"#).unwrap_err();
+    assert!(err.to_string().starts_with("missing field `frame`"));
+}
+
+#[test]
+fn test_round_trip_non_self_describing() {
+    let notify =
+        StatusNotify::decode_str(":7802160175810000380026C9030C04220303FFFFFFFF0318").unwrap();
+
+    let bytes = postcard::to_allocvec(&notify).unwrap();
+    assert_eq!(postcard::from_bytes::<StatusNotify>(&bytes).unwrap(), notify);
+}
@@ -0,0 +1,89 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use serde_json::json;
+
+use crate::StatusNotify;
+
+impl StatusNotify {
+    /// A Slack Block Kit message for `chat.postMessage` or an incoming webhook.
+    ///
+    /// The header shows `device_name`, a section the LQI and battery voltage,
+    /// and one line per DI channel, `:red_circle:` if open and
+    /// `:white_circle:` if closed. Changed channels are marked.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let message: serde_json::Value = serde_json::from_str(&notify.to_slack_message("front door")).unwrap();
+    /// assert_eq!(message["blocks"][0]["text"]["text"], "front door");
+    /// ```
+    pub fn to_slack_message(&self, device_name: &str) -> String {
+        let info = format!(
+            "*LQI* {} ({:.1} dBm)\n*Battery* {} mV",
+            self.lqi(),
+            self.lqi_dbm(),
+            self.power_voltage_millis(),
+        );
+
+        let di = self
+            .di_statuses()
+            .iter()
+            .zip(self.di_changes())
+            .enumerate()
+            .map(|(n, (open, changed))| {
+                let (emoji, state) = match open {
+                    true => (":red_circle:", "open"),
+                    false => (":white_circle:", "closed"),
+                };
+
+                let changed = if changed { " (changed)" } else { "" };
+
+                format!("{emoji} DI{} {state}{changed}", n + 1)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        json!({
+            "text": format!("{device_name}: DI {:#06b}", self.di_status() & 0x0F),
+            "blocks": [
+                {
+                    "type": "header",
+                    "text": { "type": "plain_text", "text": device_name },
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": info },
+                },
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": di },
+                },
+            ],
+        })
+        .to_string()
+    }
+}
+
+#[test]
+fn test_to_slack_message() {
+    // DI1 set and changed
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220101FFFFFFFFFFA5").unwrap();
+
+    let message: serde_json::Value =
+        serde_json::from_str(&notify.to_slack_message("81000038")).unwrap();
+
+    assert_eq!(message["text"], "81000038: DI 0b0001");
+    assert_eq!(message["blocks"][0]["type"], "header");
+    assert_eq!(message["blocks"][1]["text"]["text"], "*LQI* 117 (-57.5 dBm)\n*Battery* 3076 mV");
+    assert_eq!(
+        message["blocks"][2]["text"]["text"],
+        ":red_circle: DI1 open (changed)\n\
+         :white_circle: DI2 closed\n\
+         :white_circle: DI3 closed\n\
+         :white_circle: DI4 closed",
+    );
+}
@@ -1,17 +1,85 @@
+use crate::bytes_view::*;
 use crate::error::*;
+use crate::hardware_id::*;
+use crate::lqi_quality::*;
+
+/// Length of the ASCII wire format, including the leading `:`.
+///
+/// ```txt
+/// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
+/// ```
+pub const PACKET_ASCII_LENGTH: usize = 1 + PACKET_BINARY_LENGTH * 2;
+
+/// Length of the decoded binary buffer. See [`StatusNotify::as_bytes`].
+pub const PACKET_BINARY_LENGTH: usize = 24;
+
+/// Tick rate of [`StatusNotify::timestamp`].
+pub const TIMESTAMP_HZ: u16 = 64;
+
+/// Lowest voltage reported by [`StatusNotify::ad_is_saturated`], `0x7C` in the raw value.
+/// The ADC tops out at about 2000mV.
+pub const AD_SATURATION_MILLIS: u16 = 0x7C * 4 * 4;
+
+/// See [`StatusNotify::relay_count`].
+const MAX_RELAY_COUNT: u8 = 3;
+
+/// Odd, so [`StatusNotify::mask_sensitive_fields`] stays a bijection,
+/// which also makes it reversible by anyone who knows this constant.
+const HARDWARE_ID_SALT: u32 = 0x9E3779B9;
 
 /// Decoder of `相手端末からの状態通知`  
 /// <https://mono-wireless.com/jp/products/TWE-APPS/App_Twelite/step3-81.html>
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct StatusNotify {
-    buf: [u8; 24],
+    buf: [u8; PACKET_BINARY_LENGTH],
 }
 
-fn char2bin(c: u8) -> Result<u8, DecodeError> {
+fn char2bin(c: u8, index: usize) -> Result<u8, DecodeError> {
     match c {
         b'0'..=b'9' => Ok(c - b'0'),
         b'A'..=b'F' => Ok(c - b'A' + 10),
-        c => Err(DecodeError::InvalidCharacter(c)),
+        c if !c.is_ascii() => Err(DecodeError::NonAsciiByte(c)),
+        c => Err(DecodeError::InvalidCharacter { byte: c, index }),
+    }
+}
+
+/// `Unknown(0x00)`..`Unknown(0xFF)`, so [`StatusNotify::command_str`] needs no allocation.
+static UNKNOWN_COMMANDS: [[u8; 13]; 256] = {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut table = [*b"Unknown(0x00)"; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        table[i][10] = HEX[i >> 4];
+        table[i][11] = HEX[i & 0xF];
+        i += 1;
+    }
+
+    table
+};
+
+/// Two's complement of the sum of `buf`. See [`StatusNotify::validate_checksum`].
+fn checksum_of(buf: &[u8]) -> u8 {
+    0u8.wrapping_sub(buf.iter().fold(0u8, |s, v| s.wrapping_add(*v)))
+}
+
+/// `f64::sqrt` is not in `core`. Newton's method, for non-negative `x`.
+fn sqrt(x: f64) -> f64 {
+    if x == 0.0 {
+        return 0.0;
+    }
+
+    let mut r = if x > 1.0 { x } else { 1.0 };
+
+    loop {
+        let next = (r + x / r) / 2.0;
+
+        if next >= r {
+            return r;
+        }
+
+        r = next;
     }
 }
 
@@ -21,14 +89,17 @@ impl StatusNotify {
     /// WARNING: This method doesn't check the validity.
     /// See [`StatusNotify::validate`], If you need validator.
     pub fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
-        let len = buf.len();
+        let expected = PACKET_ASCII_LENGTH;
+        let actual = buf.len();
 
-        if len != ":7881150175810000380026C9000C04220000FFFFFFFFFFA7".len() {
-            return Err(DecodeError::InvalidLength(len));
+        if actual != expected {
+            return Err(DecodeError::InvalidLength { expected, actual });
         }
 
-        if buf[0] != b':' {
-            return Err(DecodeError::InvalidCharacter(buf[0]));
+        match buf[0] {
+            b':' => {}
+            c if !c.is_ascii() => return Err(DecodeError::NonAsciiByte(c)),
+            c => return Err(DecodeError::InvalidCharacter { byte: c, index: 0 }),
         }
 
         let buf = &buf[1..];
@@ -38,8 +109,8 @@ impl StatusNotify {
         };
 
         for (n, out) in out.buf.iter_mut().enumerate() {
-            *out |= char2bin(buf[n * 2])? << 4;
-            *out |= char2bin(buf[n * 2 + 1])?;
+            *out |= char2bin(buf[n * 2], 1 + n * 2)? << 4;
+            *out |= char2bin(buf[n * 2 + 1], 1 + n * 2 + 1)?;
         }
 
         Ok(out)
@@ -53,6 +124,120 @@ impl StatusNotify {
         Self::decode(buf.as_bytes())
     }
 
+    /// Decode value by consuming exactly [`PACKET_ASCII_LENGTH`] bytes from `iter`.
+    ///
+    /// WARNING: This method doesn't check the validity.
+    /// See [`StatusNotify::validate`], If you need validator.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let mut iter = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7\r\n".bytes();
+    /// let notify = StatusNotify::decode_from_iterator(&mut iter).unwrap();
+    /// assert_eq!(notify.source_device_id(), 0x78);
+    /// assert_eq!(iter.next(), Some(b'\r'));
+    /// ```
+    pub fn decode_from_iterator<I: Iterator<Item = u8>>(iter: I) -> Result<Self, DecodeError> {
+        let mut buf = [0u8; PACKET_ASCII_LENGTH];
+        let mut actual = 0;
+
+        for (out, c) in buf.iter_mut().zip(iter) {
+            *out = c;
+            actual += 1;
+        }
+
+        if actual != PACKET_ASCII_LENGTH {
+            return Err(DecodeError::UnexpectedEof {
+                expected: PACKET_ASCII_LENGTH,
+                actual,
+            });
+        }
+
+        Self::decode(&buf)
+    }
+
+    /// Decode the space-separated hex bytes Wireshark exports, in either case.
+    ///
+    /// All ASCII whitespace is ignored and no leading `:` is expected.
+    /// Error positions count the hex digits only, starting from 1.
+    ///
+    /// WARNING: This method doesn't check the validity.
+    /// See [`StatusNotify::validate`], If you need validator.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let notify = StatusNotify::from_wireshark_hex(
+    ///     "78 81 15 01 75 81 00 00 38 00 26 c9 00 0c 04 22 00 00 ff ff ff ff ff a7",
+    /// ).unwrap();
+    /// assert_eq!(notify, StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap());
+    /// ```
+    pub fn from_wireshark_hex(s: &str) -> Result<Self, DecodeError> {
+        let hex = s
+            .bytes()
+            .filter(|c| !c.is_ascii_whitespace())
+            .map(|c| c.to_ascii_uppercase());
+
+        let mut iter = core::iter::once(b':').chain(hex);
+        let notify = Self::decode_from_iterator(&mut iter)?;
+
+        let rest = iter.count();
+
+        if rest != 0 {
+            return Err(DecodeError::InvalidLength {
+                expected: PACKET_ASCII_LENGTH,
+                actual: PACKET_ASCII_LENGTH + rest,
+            });
+        }
+
+        Ok(notify)
+    }
+
+    /// Decode value by [`&str`] without any error checking, for trusted input only.
+    ///
+    /// The leading `:` and every hex digit are taken as-is, so a corrupted input
+    /// silently decodes to garbage instead of an error.
+    /// NEVER use this for untrusted input such as the serial line; use [`StatusNotify::decode_str`].
+    ///
+    /// # Panics
+    /// Panics if `s` is shorter than [`PACKET_ASCII_LENGTH`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let notify = StatusNotify::decode_from_hex_str_trusted(":7881150175810000380026C9000C04220000FFFFFFFFFFA7");
+    /// assert_eq!(notify.hardware_id(), 0x81000038);
+    /// ```
+    pub fn decode_from_hex_str_trusted(s: &str) -> Self {
+        let buf = &s.as_bytes()[1..PACKET_ASCII_LENGTH];
+
+        // '0'..='9' is 0x3X, 'A'..='F' is 0x4X and 'a'..='f' is 0x6X
+        let nibble = |c: u8| (c & 0x0F) + 9 * (c >> 6);
+
+        let mut out = Self {
+            buf: Default::default(),
+        };
+
+        for (n, out) in out.buf.iter_mut().enumerate() {
+            *out = nibble(buf[n * 2]) << 4 | nibble(buf[n * 2 + 1]);
+        }
+
+        out
+    }
+
+    /// Decode value by [`&str`], then [`StatusNotify::validate`] it.
+    ///
+    /// ```
+    /// # use twelite_serial::*;
+    /// let notify = StatusNotify::decode_validated(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.source_device_id(), 0x78);
+    ///
+    /// let err = StatusNotify::decode_validated(":7881150175810000380026C9000C04220000FFFFFFFFFFFF").unwrap_err();
+    /// assert_eq!(err, TweliteError::Validate(ValidateError::InvalidChecksum(0x58)));
+    /// ```
+    pub fn decode_validated(buf: &str) -> Result<Self, TweliteError> {
+        let notify = Self::decode_str(buf)?;
+        notify.validate()?;
+        Ok(notify)
+    }
+
     /// # Byte position
     /// ```txt
     /// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
@@ -95,6 +280,23 @@ impl StatusNotify {
         self.buf[1]
     }
 
+    /// Human-readable [`StatusNotify::command`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.command_str(), "StatusNotify");
+    ///
+    /// let notify = StatusNotify::decode_str(":7880150175810000380026C9000C04220000FFFFFFFFFFA8").unwrap();
+    /// assert_eq!(notify.command_str(), "Unknown(0x80)");
+    /// ```
+    pub fn command_str(&self) -> &'static str {
+        match self.command() {
+            0x81 => "StatusNotify",
+            c => core::str::from_utf8(&UNKNOWN_COMMANDS[c as usize]).unwrap(),
+        }
+    }
+
     /// # Byte position
     /// ```txt
     /// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
@@ -183,6 +385,53 @@ impl StatusNotify {
         u32::from_be_bytes([self.buf[5], self.buf[6], self.buf[7], self.buf[8]])
     }
 
+    /// Typed version of [`StatusNotify::hardware_id`], which also formats
+    /// without allocating.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.hardware_id_typed().to_string(), "81000038");
+    /// assert_eq!(format!("device={:#}", notify.hardware_id_typed()), "device=0x81000038");
+    /// ```
+    pub fn hardware_id_typed(&self) -> HardwareId {
+        HardwareId::new(self.hardware_id())
+    }
+
+    /// [`StatusNotify::hardware_id`] with its bytes reversed, for systems
+    /// that read the ID in little-endian order.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.hardware_id_endian_swap(), 0x38000081);
+    /// ```
+    pub fn hardware_id_endian_swap(&self) -> u32 {
+        self.hardware_id().swap_bytes()
+    }
+
+    /// [`StatusNotify::hardware_id`] in little-endian byte order.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.hardware_id_le_bytes(), [0x38, 0x00, 0x00, 0x81]);
+    /// ```
+    pub fn hardware_id_le_bytes(&self) -> [u8; 4] {
+        self.hardware_id().to_le_bytes()
+    }
+
+    /// [`StatusNotify::hardware_id`] in big-endian byte order, as on the wire.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.hardware_id_be_bytes(), [0x81, 0x00, 0x00, 0x38]);
+    /// ```
+    pub fn hardware_id_be_bytes(&self) -> [u8; 4] {
+        self.hardware_id().to_be_bytes()
+    }
+
     /// # Byte position
     /// ```txt
     /// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
@@ -225,6 +474,37 @@ impl StatusNotify {
         u16::from_be_bytes([self.buf[10], self.buf[11]])
     }
 
+    /// [`StatusNotify::timestamp`] as [`core::time::Duration`].
+    ///
+    /// The timestamp wraps at 0xFFFF, roughly every 1024 seconds.
+    /// So this is NOT an uptime; compare only nearby packets.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.timestamp_duration().as_micros(), 155_140_625);
+    /// ```
+    pub fn timestamp_duration(&self) -> core::time::Duration {
+        let nanos = self.timestamp() as u64 * 1_000_000_000 / TIMESTAMP_HZ as u64;
+        core::time::Duration::from_nanos(nanos)
+    }
+
+    /// [`StatusNotify::timestamp`] in seconds. See [`StatusNotify::timestamp_duration`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.timestamp_seconds(), 155.140625);
+    /// ```
+    pub fn timestamp_seconds(&self) -> f32 {
+        self.timestamp() as f32 / TIMESTAMP_HZ as f32
+    }
+
+    /// Tick rate of [`StatusNotify::timestamp`]. Same as [`TIMESTAMP_HZ`].
+    pub const fn timestamp_hz() -> u16 {
+        TIMESTAMP_HZ
+    }
+
     /// # Byte position
     /// ```txt
     /// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
@@ -251,6 +531,32 @@ impl StatusNotify {
         self.buf[12]
     }
 
+    /// Check the packet is relayed at least once. See [`StatusNotify::relay_count`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(!notify.is_relayed());
+    /// ```
+    pub fn is_relayed(&self) -> bool {
+        self.relay_count() > 0
+    }
+
+    /// [`StatusNotify::relay_count`], or `None` if it is out of range.
+    /// Same rule as [`StatusNotify::validate_relay_count`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.relay_count_checked(), Some(0));
+    ///
+    /// let notify = StatusNotify::decode_str(":7881150175810000380026C9FF0C04220000FFFFFFFFFFA8").unwrap();
+    /// assert_eq!(notify.relay_count_checked(), None);
+    /// ```
+    pub fn relay_count_checked(&self) -> Option<u8> {
+        self.validate_relay_count().ok().map(|_| self.relay_count())
+    }
+
     /// # Byte position
     /// ```txt
     /// :7881150175810000380026C9000C04220000FFFFFFFFFFA7
@@ -273,6 +579,41 @@ impl StatusNotify {
         u16::from_be_bytes([self.buf[13], self.buf[14]])
     }
 
+    /// [`StatusNotify::power_voltage_millis`] in volts.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.power_voltage_volts(), 3.076); // [V]
+    /// ```
+    pub fn power_voltage_volts(&self) -> f32 {
+        self.power_voltage_millis() as f32 / 1000.0
+    }
+
+    /// [`StatusNotify::power_voltage_millis`] plus a signed `offset_mv`, saturating at `0..=65535`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.power_voltage_millis_calibrated(-76), 3000); // [mV]
+    /// assert_eq!(notify.power_voltage_millis_calibrated(i16::MIN), 0);
+    /// ```
+    pub fn power_voltage_millis_calibrated(&self, offset_mv: i16) -> u16 {
+        self.power_voltage_millis().saturating_add_signed(offset_mv)
+    }
+
+    /// Check [`StatusNotify::power_voltage_millis`] is below `threshold_millis`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(!notify.is_low_battery(2700));
+    /// assert!(notify.is_low_battery(3100));
+    /// ```
+    pub fn is_low_battery(&self, threshold_millis: u16) -> bool {
+        self.power_voltage_millis() < threshold_millis
+    }
+
 
     /// If you need separated value, you can use likes [`StatusNotify::di1_status`].
     /// # Byte position
@@ -434,7 +775,139 @@ impl StatusNotify {
 
     /// See [`StatusNotify::lqi`].
     pub fn lqi_dbm(&self) -> f32 {
-        (7 * self.lqi() as i32 - 1970) as f32 / 20.0
+        Self::lqi_to_dbm(self.lqi())
+    }
+
+    /// [`StatusNotify::lqi`] linearly rescaled to `0..=100`, rounded.
+    ///
+    /// For display only, e.g. a compact gauge. This is not a calibrated
+    /// link quality; use [`StatusNotify::lqi_dbm`] for a measurement.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.lqi(), 117);
+    /// assert_eq!(notify.lqi_percent(), 46);
+    /// ```
+    pub fn lqi_percent(&self) -> u8 {
+        ((self.lqi() as u16 * 100 + 127) / 255) as u8
+    }
+
+    /// Classify [`StatusNotify::lqi`] into the bands from the reference.
+    ///
+    /// ```
+    /// # use twelite_serial::{LqiQuality, StatusNotify};
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.lqi(), 117);
+    /// assert_eq!(notify.lqi_quality(), LqiQuality::Good);
+    /// ```
+    pub fn lqi_quality(&self) -> LqiQuality {
+        LqiQuality::from_lqi(self.lqi())
+    }
+
+    /// Convert any LQI value to dBm without a packet. See [`StatusNotify::lqi`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// assert_eq!(StatusNotify::lqi_to_dbm(125), -54.75);
+    /// ```
+    pub const fn lqi_to_dbm(lqi: u8) -> f32 {
+        (7 * lqi as i32 - 1970) as f32 / 20.0
+    }
+
+    /// `(min, max)` of [`StatusNotify::lqi_to_dbm`] over the whole LQI scale.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// assert_eq!(StatusNotify::lqi_dbm_range(), (-98.5, -9.25));
+    /// ```
+    pub const fn lqi_dbm_range() -> (f32, f32) {
+        (Self::lqi_to_dbm(u8::MIN), Self::lqi_to_dbm(u8::MAX))
+    }
+
+    /// Sample standard deviation of [`StatusNotify::lqi`] over `window`.
+    ///
+    /// Near 0.0 is a stable link. Above about 30 the link is marginal,
+    /// and a relay may help even if the average LQI looks fine.
+    /// Returns 0.0 for fewer than two packets.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(StatusNotify::lqi_stability_over(&[notify, notify, notify]), 0.0);
+    /// ```
+    pub fn lqi_stability_over(window: &[StatusNotify]) -> f32 {
+        if window.len() < 2 {
+            return 0.0;
+        }
+
+        let n = window.len() as f64;
+        let mean = window.iter().map(|v| v.lqi() as f64).sum::<f64>() / n;
+
+        let variance = window
+            .iter()
+            .map(|v| (v.lqi() as f64 - mean) * (v.lqi() as f64 - mean))
+            .sum::<f64>()
+            / (n - 1.0);
+
+        sqrt(variance) as f32
+    }
+
+    /// Check [`StatusNotify::source_device_id`] is in `start..=end`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(notify.source_device_id_in_range(0x71, 0x78));
+    /// assert!(!notify.source_device_id_in_range(0x01, 0x10));
+    /// ```
+    pub fn source_device_id_in_range(&self, start: u8, end: u8) -> bool {
+        (start..=end).contains(&self.source_device_id())
+    }
+
+    /// `true` if [`StatusNotify::source_device_id`] is even, `false` if odd.
+    ///
+    /// For deployments which tell device classes apart by the ID parity.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(notify.source_device_id_parity());
+    /// ```
+    pub fn source_device_id_parity(&self) -> bool {
+        self.source_device_id().is_multiple_of(2)
+    }
+
+    /// Zero-based index of a child device ID (0x01..=0x64), for array-backed
+    /// per-device tables. `None` for the parent (0x00) and special IDs above 0x64.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// // 0x78 is the ID of an unconfigured child.
+    /// assert_eq!(notify.source_device_id_to_index(), None);
+    /// ```
+    pub fn source_device_id_to_index(&self) -> Option<usize> {
+        match self.source_device_id() {
+            id @ 0x01..=0x64 => Some(id as usize - 1),
+            _ => None,
+        }
+    }
+
+    /// Check the top `bits` bits of [`StatusNotify::hardware_id`] match `prefix`, like a CIDR block.
+    ///
+    /// `bits = 0` matches everything; `bits >= 32` requires an exact match.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(notify.source_hardware_id_matches_prefix(0x81000000, 8));
+    /// assert!(!notify.source_hardware_id_matches_prefix(0x82000000, 8));
+    /// ```
+    pub fn source_hardware_id_matches_prefix(&self, prefix: u32, bits: u8) -> bool {
+        let mask = u32::MAX.checked_shl(32 - bits.min(32) as u32).unwrap_or(0);
+
+        (self.hardware_id() ^ prefix) & mask == 0
     }
 
     /// Represent digital input status as [`bool`]. See [`StatusNotify::di_status`].
@@ -477,6 +950,42 @@ impl StatusNotify {
         (self.di_changed() & (1 << 3)) != 0
     }
 
+    /// All of [`StatusNotify::di1_status`]..[`StatusNotify::di4_status`], DI1 first.
+    pub fn di_statuses(&self) -> [bool; 4] {
+        [
+            self.di1_status(),
+            self.di2_status(),
+            self.di3_status(),
+            self.di4_status(),
+        ]
+    }
+
+    /// All of [`StatusNotify::di1_changed`]..[`StatusNotify::di4_changed`], DI1 first.
+    pub fn di_changes(&self) -> [bool; 4] {
+        [
+            self.di1_changed(),
+            self.di2_changed(),
+            self.di3_changed(),
+            self.di4_changed(),
+        ]
+    }
+
+    /// Indexed version of [`StatusNotify::di_statuses`], so `n = 0` is DI1.
+    ///
+    /// Returns `None` if `n` is not in `0..4`.
+    pub fn di_status_n(&self, n: usize) -> Option<bool> {
+        self.di_statuses().get(n).copied()
+    }
+
+    /// Per-channel change flags computed from [`StatusNotify::di_status`] of `prev` and `self`, DI1 first.
+    ///
+    /// Unlike [`StatusNotify::di_changes`], this does not trust the device's own flags.
+    /// Both frames are assumed to be from the same [`StatusNotify::hardware_id`].
+    pub fn di_diff(&self, prev: &StatusNotify) -> [bool; 4] {
+        let diff = self.di_status() ^ prev.di_status();
+        core::array::from_fn(|n| diff & (1 << n) != 0)
+    }
+
     /// See [`StatusNotify::ad1_value`].
     pub fn ad_value(&self) -> [u8; 4] {
         [
@@ -538,14 +1047,94 @@ impl StatusNotify {
         (value * 4 + fix) * 4
     }
 
-    /// Check the checksum.
-    ///
-    /// If unexpected value is comming, the value sends as Err(u8).
-    ///
-    /// If you need validate totally, you can use [`StatusNotify::validate`].
-    ///
-    /// # Official Reference
-    /// チェックサムとは受け取ったデータが正しいかどうかを確認するための付加データです。
+    /// All of [`StatusNotify::ad1_voltage_millis`]..[`StatusNotify::ad4_voltage_millis`], AD1 first.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// // (0xFF * 4 + 0b11) * 4
+    /// assert_eq!(notify.ad_voltages_millis(), [4092, 4092, 4092, 4092]);
+    /// ```
+    pub fn ad_voltages_millis(&self) -> [u16; 4] {
+        [
+            self.ad1_voltage_millis(),
+            self.ad2_voltage_millis(),
+            self.ad3_voltage_millis(),
+            self.ad4_voltage_millis(),
+        ]
+    }
+
+    /// Official names of the analog inputs, in the same order as
+    /// [`StatusNotify::ad_voltages_millis`].
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// assert_eq!(StatusNotify::ad_channel_names(), ["AI1", "AI2", "AI3", "AI4"]);
+    /// ```
+    pub const fn ad_channel_names() -> [&'static str; 4] {
+        ["AI1", "AI2", "AI3", "AI4"]
+    }
+
+    /// All of [`StatusNotify::ad1_fix`]..[`StatusNotify::ad4_fix`], AD1 first.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.ad_fixes(), [0b11, 0b11, 0b11, 0b11]);
+    /// ```
+    pub fn ad_fixes(&self) -> [u8; 4] {
+        [self.ad1_fix(), self.ad2_fix(), self.ad3_fix(), self.ad4_fix()]
+    }
+
+    /// Indexed version of [`StatusNotify::ad1_voltage_millis`] and friends.
+    ///
+    /// Returns `None` if `ch` is not in `1..=4`,
+    /// or if the channel value is `0xFF` (2000mV or more, which is invalid).
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.ad_voltage_at_channel(0), None);
+    /// assert_eq!(notify.ad_voltage_at_channel(1), None); // 0xFF
+    /// assert_eq!(notify.ad_voltage_at_channel(5), None);
+    /// ```
+    pub fn ad_voltage_at_channel(&self, ch: u8) -> Option<u16> {
+        let (value, voltage) = match ch {
+            1 => (self.ad1_value(), self.ad1_voltage_millis()),
+            2 => (self.ad2_value(), self.ad2_voltage_millis()),
+            3 => (self.ad3_value(), self.ad3_voltage_millis()),
+            4 => (self.ad4_value(), self.ad4_voltage_millis()),
+            _ => return None,
+        };
+
+        (value != 0xFF).then_some(voltage)
+    }
+
+    /// Check whether A/D channel `ch` (`1..=4`) is at the top of the ADC range.
+    ///
+    /// True when [`StatusNotify::ad_voltage_at_channel`] is at least
+    /// [`AD_SATURATION_MILLIS`]. An invalid (`0xFF`) channel is not saturated.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert!(!notify.ad_is_saturated(1)); // 0xFF
+    /// ```
+    pub fn ad_is_saturated(&self, ch: usize) -> bool {
+        u8::try_from(ch)
+            .ok()
+            .and_then(|ch| self.ad_voltage_at_channel(ch))
+            .is_some_and(|voltage| voltage >= AD_SATURATION_MILLIS)
+    }
+
+    /// Check the checksum.
+    ///
+    /// If unexpected value is comming, the value sends as Err(u8).
+    ///
+    /// If you need validate totally, you can use [`StatusNotify::validate`].
+    ///
+    /// # Official Reference
+    /// チェックサムとは受け取ったデータが正しいかどうかを確認するための付加データです。
     ///
     /// データ部の各バイトの和を８ビット幅で計算し２の補数をとります。
     /// つまりデータ部の各バイトの総和＋チェックサムバイトを８ビット幅で計算すると０になります。
@@ -587,7 +1176,24 @@ impl StatusNotify {
     /// If you need validate totally, you can use [`StatusNotify::validate`].
     pub fn validate_relay_count(&self) -> Result<(), u8> {
         let relay_count = self.relay_count();
-        (relay_count <= 3).then_some(()).ok_or(relay_count)
+        (relay_count <= MAX_RELAY_COUNT).then_some(()).ok_or(relay_count)
+    }
+
+    /// Check the frame was received directly, not through a repeater.
+    ///
+    /// If relayed, the relay count sends as Err(u8).
+    ///
+    /// This is stricter than [`StatusNotify::validate`], for installations
+    /// where every device is in direct range.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.validate_no_relay(), Ok(()));
+    /// ```
+    pub fn validate_no_relay(&self) -> Result<(), u8> {
+        let relay_count = self.relay_count();
+        (relay_count == 0).then_some(()).ok_or(relay_count)
     }
 
     /// Validate totally.
@@ -607,15 +1213,322 @@ impl StatusNotify {
         Ok(())
     }
 
+    /// Check every reserved bit is 0, for strict mode.
+    ///
+    /// Only DI1..DI4 are defined, so the upper nibbles of
+    /// [`StatusNotify::di_status`] and [`StatusNotify::di_changed`] are reserved.
+    /// Byte 15 is unused by the reference but not reserved, and is not checked.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// notify.validate_all_reserved_bits().unwrap();
+    /// ```
+    pub fn validate_all_reserved_bits(&self) -> Result<(), ReservedBitError> {
+        let fields = [("di_status", 16, 0x0F), ("di_changed", 17, 0x0F)];
+
+        for (field, byte, expected_mask) in fields {
+            let actual = self.buf[byte as usize];
+
+            if actual & !expected_mask != 0 {
+                return Err(ReservedBitError {
+                    field,
+                    byte,
+                    expected_mask,
+                    actual,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check [`StatusNotify::packet_id`] advanced by at most `max_gap` since
+    /// `prev_packet_id`, the packet id of the previous frame of the same device.
+    /// Rolls over from 255 to 0.
+    ///
+    /// ```
+    /// # use twelite_serial::{PacketIdError, StatusNotify};
+    /// let prev = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let next = StatusNotify::decode_str(":7881180175810000380026C9000C04220000FFFFFFFFFFA4").unwrap();
+    ///
+    /// assert_eq!(next.validate_packet_id_sequence(prev.packet_id(), 3), Ok(()));
+    /// assert_eq!(
+    ///     next.validate_packet_id_sequence(prev.packet_id(), 1),
+    ///     Err(PacketIdError::GapTooLarge { expected: 0x16, got: 0x18, gap: 3 }),
+    /// );
+    /// ```
+    pub fn validate_packet_id_sequence(&self, prev_packet_id: u8, max_gap: u8) -> Result<(), PacketIdError> {
+        let gap = self.packet_id().wrapping_sub(prev_packet_id);
+
+        if gap > max_gap {
+            return Err(PacketIdError::GapTooLarge {
+                expected: prev_packet_id.wrapping_add(1),
+                got: self.packet_id(),
+                gap,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// `true` if [`StatusNotify::packet_id`] wrapped from 255 to 0 since `prev`,
+    /// the packet id of the previous frame of the same device.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.packet_id(), 0x15);
+    /// assert!(notify.packet_id_rolled_over(0xFE));
+    /// assert!(!notify.packet_id_rolled_over(0x14));
+    /// ```
+    pub fn packet_id_rolled_over(&self, prev: u8) -> bool {
+        self.packet_id() < prev
+    }
+
+    /// [`StatusNotify::packet_id`] extended to 16 bits, counting rollovers since
+    /// `prev_u16`, the value returned for the previous frame of the same device.
+    /// Start with `0` or the first [`StatusNotify::packet_id`].
+    ///
+    /// Increases monotonically as long as fewer than 256 frames are missed in
+    /// a row, and wraps from 65535 to 0.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.packet_id_u16(0x0210), 0x0215);
+    /// assert_eq!(notify.packet_id_u16(0x02FE), 0x0315);
+    /// ```
+    pub fn packet_id_u16(&self, prev_u16: u16) -> u16 {
+        let rollovers = match self.packet_id_rolled_over(prev_u16 as u8) {
+            true => (prev_u16 >> 8).wrapping_add(1),
+            false => prev_u16 >> 8,
+        };
+
+        rollovers << 8 | self.packet_id() as u16
+    }
+
+    /// Copy with [`StatusNotify::hardware_id`] pseudonymized, for logging.
+    ///
+    /// This is a lightweight `wrapping_mul` by a fixed, public salt, not a cryptographic hash.
+    /// The same device always maps to the same masked id, and the mapping is
+    /// reversible: multiplying by the salt's modular inverse gives the original id back.
+    /// It keeps ids out of casual view, but does not hide them from anyone who reads this source.
+    /// The checksum is adjusted, so [`StatusNotify::validate_checksum`] gives the same result as before.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let masked = notify.mask_sensitive_fields();
+    /// assert_ne!(masked.hardware_id(), notify.hardware_id());
+    /// assert_eq!(masked.lqi(), notify.lqi());
+    /// masked.validate().unwrap();
+    /// ```
+    pub fn mask_sensitive_fields(&self) -> StatusNotify {
+        let mut buf = self.buf;
+
+        let masked = self.hardware_id().wrapping_mul(HARDWARE_ID_SALT).to_be_bytes();
+        let before = buf[5..9].iter().fold(0u8, |s, v| s.wrapping_add(*v));
+        let after = masked.iter().fold(0u8, |s, v| s.wrapping_add(*v));
+
+        buf[5..9].copy_from_slice(&masked);
+        buf[23] = buf[23].wrapping_add(before).wrapping_sub(after);
+
+        Self { buf }
+    }
+
+    /// Copy with [`StatusNotify::relay_count`] incremented and the checksum recomputed,
+    /// as if the packet were forwarded by a relay.
+    ///
+    /// Returns `None` if the relay count is already 3 (maximum) or more.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let relayed = notify.relay_count_increment().unwrap();
+    /// assert_eq!(relayed.relay_count(), 1);
+    /// relayed.validate().unwrap();
+    /// ```
+    pub fn relay_count_increment(&self) -> Option<StatusNotify> {
+        if self.relay_count() >= MAX_RELAY_COUNT {
+            return None;
+        }
+
+        let mut buf = self.buf;
+        buf[12] += 1;
+        buf[23] = checksum_of(&buf[..23]);
+
+        Some(Self { buf })
+    }
+
+    /// Copy with [`StatusNotify::power_voltage_millis`] replaced by
+    /// [`StatusNotify::power_voltage_millis_calibrated`] and the checksum recomputed,
+    /// so every consumer of the frame sees the calibrated value.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let calibrated = notify.with_power_voltage_calibrated(-76);
+    /// assert_eq!(calibrated.power_voltage_millis(), 3000); // [mV]
+    /// calibrated.validate().unwrap();
+    /// ```
+    pub fn with_power_voltage_calibrated(&self, offset_mv: i16) -> Self {
+        let mut buf = self.buf;
+        buf[13..15].copy_from_slice(&self.power_voltage_millis_calibrated(offset_mv).to_be_bytes());
+        buf[23] = checksum_of(&buf[..23]);
+
+        Self { buf }
+    }
+
+    /// Copy with [`StatusNotify::checksum`] recomputed from the other bytes,
+    /// so [`StatusNotify::validate_checksum`] passes.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// let broken = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFF00").unwrap();
+    /// assert!(broken.validate_checksum().is_err());
+    /// assert_eq!(broken.force_checksum_correct().checksum(), 0xA7);
+    /// broken.force_checksum_correct().validate_checksum().unwrap();
+    /// ```
+    pub fn force_checksum_correct(&self) -> Self {
+        let mut buf = self.buf;
+        buf[23] = checksum_of(&buf[..23]);
+
+        Self { buf }
+    }
+
+    /// Get named reference of raw value.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// let view = notify.bytes_view();
+    /// assert_eq!(*view.command_raw, 0x81);
+    /// assert_eq!(view.hardware_id_raw, &[0x81, 0x00, 0x00, 0x38]);
+    /// ```
+    pub fn bytes_view(&self) -> StatusNotifyBytesView<'_> {
+        StatusNotifyBytesView::new(&self.buf)
+    }
+
     /// Get reference of raw value.
-    pub fn as_bytes(&self) -> &[u8; 24] {
+    pub fn as_bytes(&self) -> &[u8; PACKET_BINARY_LENGTH] {
         &self.buf
     }
 
+    /// First raw byte which differs from `other`, as `(index, self_byte, other_byte)`.
+    ///
+    /// ```
+    /// # use twelite_serial::StatusNotify;
+    /// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+    /// assert_eq!(notify.first_different_byte(&notify), None);
+    ///
+    /// let relayed = notify.relay_count_increment().unwrap();
+    /// assert_eq!(notify.first_different_byte(&relayed), Some((12, 0x00, 0x01)));
+    /// ```
+    pub fn first_different_byte(&self, other: &StatusNotify) -> Option<(usize, u8, u8)> {
+        self.buf
+            .iter()
+            .zip(other.buf)
+            .enumerate()
+            .find(|(_, (a, b))| *a != b)
+            .map(|(n, (a, b))| (n, *a, b))
+    }
+
     /// Drop out to raw value.
-    pub fn into_bytes(self) -> [u8; 24] {
+    pub fn into_bytes(self) -> [u8; PACKET_BINARY_LENGTH] {
         self.buf
     }
+
+    /// Construct from raw value. Inverse of [`StatusNotify::into_bytes`].
+    ///
+    /// WARNING: This method doesn't check the validity.
+    /// See [`StatusNotify::validate`], If you need validator.
+    #[doc(alias = "from_raw_buffer")]
+    pub fn from_bytes(buf: [u8; PACKET_BINARY_LENGTH]) -> Self {
+        Self { buf }
+    }
+}
+
+
+
+/// Concise one-line summary.
+///
+/// ```
+/// # use twelite_serial::StatusNotify;
+/// # let notify = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+/// assert_eq!(notify.to_string(), "-57.55dBm 3076mV is_open: false changed: false");
+/// ```
+impl core::fmt::Display for StatusNotify {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let dbm = self.lqi_dbm();
+        let mv = self.power_voltage_millis();
+        let open = self.di1_status();
+        let changed = self.di1_changed();
+
+        write!(f, "{dbm:.2}dBm {mv}mV is_open: {open} changed: {changed}")
+    }
+}
+
+
+/// Hashes the raw bytes, consistent with [`Eq`].
+impl core::hash::Hash for StatusNotify {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write(&self.buf);
+    }
+}
+
+/// Ordered by [`StatusNotify::hardware_id`], then [`StatusNotify::timestamp`].
+///
+/// Timestamps are compared as raw ticks. A rollover-aware comparison isn't
+/// transitive, so it can't back a total order; sort within a rollover window
+/// if you need it. Remaining ties are broken by the raw bytes to stay
+/// consistent with [`Eq`].
+impl Ord for StatusNotify {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.hardware_id()
+            .cmp(&other.hardware_id())
+            .then_with(|| self.timestamp().cmp(&other.timestamp()))
+            .then_with(|| self.buf.cmp(&other.buf))
+    }
+}
+
+impl PartialOrd for StatusNotify {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Same as [`StatusNotify::decode_str`].
+///
+/// ```
+/// # use twelite_serial::StatusNotify;
+/// let notify: StatusNotify = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7".parse().unwrap();
+/// assert_eq!(notify.source_device_id(), 0x78);
+/// ```
+impl core::str::FromStr for StatusNotify {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_str(s)
+    }
+}
+
+/// Same as [`StatusNotify::decode`].
+impl TryFrom<&[u8]> for StatusNotify {
+    type Error = DecodeError;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        Self::decode(buf)
+    }
+}
+
+/// Same as [`StatusNotify::decode_str`].
+impl TryFrom<&str> for StatusNotify {
+    type Error = DecodeError;
+
+    fn try_from(buf: &str) -> Result<Self, Self::Error> {
+        Self::decode_str(buf)
+    }
 }
 
 #[test]
@@ -642,30 +1555,384 @@ fn test() {
     assert_eq!(Ok(()), notify.validate());
 
 
-    // Invalid Checksum
+    // Invalid Length
+    let err = StatusNotify::decode_str(":7881150175810000380026C9000C04").unwrap_err();
+
+    assert_eq!(DecodeError::InvalidLength { expected: PACKET_ASCII_LENGTH, actual: 31 }, err);
+    assert_eq!(PACKET_ASCII_LENGTH, 49);
+
+
+    // Invalid Character
+    let err = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFAZ").unwrap_err();
+
+    assert_eq!(DecodeError::InvalidCharacter { byte: b'Z', index: 48 }, err);
+
+    let err = StatusNotify::decode_str("#7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap_err();
+
+    assert_eq!(DecodeError::InvalidCharacter { byte: b'#', index: 0 }, err);
+
+
+    // Non-ASCII Byte
+    let mut buf = *b":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+    buf[10] = 0xE3;
+
+    assert_eq!(Err(DecodeError::NonAsciiByte(0xE3)), StatusNotify::decode(&buf).map(|_| ()));
+}
+
+#[test]
+fn test_decode_from_hex_str_trusted() {
+    let frame = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+
+    assert_eq!(
+        StatusNotify::decode_from_hex_str_trusted(frame),
+        StatusNotify::decode_str(frame).unwrap(),
+    );
+
+    assert_eq!(
+        StatusNotify::decode_from_hex_str_trusted(&frame.to_ascii_lowercase()),
+        StatusNotify::decode_str(frame).unwrap(),
+    );
+}
+
+#[test]
+fn test_decode_from_iterator() {
+    let frame = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+
+    assert_eq!(
+        StatusNotify::decode_from_iterator(frame.bytes()),
+        StatusNotify::decode_str(frame),
+    );
+
+    assert_eq!(
+        StatusNotify::decode_from_iterator(frame.bytes().take(10)),
+        Err(DecodeError::UnexpectedEof { expected: PACKET_ASCII_LENGTH, actual: 10 }),
+    );
+}
+
+#[test]
+fn test_conversions() {
+    let frame = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+    let notify = StatusNotify::decode_str(frame).unwrap();
+
+    let parsed: StatusNotify = frame.parse().unwrap();
+    assert_eq!(parsed.as_bytes(), notify.as_bytes());
+
+    let parsed = StatusNotify::try_from(frame).unwrap();
+    assert_eq!(parsed.as_bytes(), notify.as_bytes());
+
+    let parsed = StatusNotify::try_from(frame.as_bytes()).unwrap();
+    assert_eq!(parsed.as_bytes(), notify.as_bytes());
+
+    assert_eq!(
+        Err(DecodeError::InvalidLength { expected: 49, actual: 0 }),
+        "".parse::<StatusNotify>().map(|_| ()),
+    );
+}
+
+#[test]
+fn test_eq_hash() {
+    use core::hash::{Hash, Hasher};
+    use std::hash::DefaultHasher;
+
+    let frame = ":7881150175810000380026C9000C04220000FFFFFFFFFFA7";
+    let a = StatusNotify::decode_str(frame).unwrap();
+    let b = StatusNotify::decode_str(frame).unwrap();
+
+    assert_eq!(a, b);
+
+    let hash = |notify: &StatusNotify| {
+        let mut hasher = DefaultHasher::new();
+        notify.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn test_ord() {
+    // hardware_id = 0x81000038, timestamp = 0x26C9
+    let a = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // hardware_id = 0x81000038, timestamp = 0x26CA
+    let b = StatusNotify::decode_str(":7881150175810000380026CA000C04220000FFFFFFFFFFA6").unwrap();
+
+    // hardware_id = 0x81000039, timestamp = 0x0000
+    let c = StatusNotify::decode_str(":788115017581000039000000000C04220000FFFFFFFFFFA7").unwrap();
+
+    let mut packets = [c, b, a];
+    packets.sort();
+
+    assert_eq!(packets[0].timestamp(), 0x26C9);
+    assert_eq!(packets[1].timestamp(), 0x26CA);
+    assert_eq!(packets[2].hardware_id(), 0x81000039);
+}
+
+#[test]
+fn test_relay_count_increment() {
     let notify =
-        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFFF").unwrap();
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
 
-    assert_eq!(Err(ValidateError::InvalidChecksum(0xFFu8 - 0xA7)), notify.validate());
+    let notify = notify.relay_count_increment().unwrap();
+    let notify = notify.relay_count_increment().unwrap();
+    let notify = notify.relay_count_increment().unwrap();
 
+    assert_eq!(notify.relay_count(), 3);
+    assert_eq!(notify.checksum(), 0xA4);
+    assert_eq!(Ok(()), notify.validate());
+    assert!(notify.relay_count_increment().is_none());
+}
 
-    // Invalid Protocol Version
+#[test]
+fn test_di_arrays() {
+    // di_status = DI1 | DI3, di_changed = DI3 | DI4
     let notify =
-        StatusNotify::decode_str(":7881150075810000380026C9000C04220000FFFFFFFFFFA8").unwrap();
+        StatusNotify::decode_str(":7881150175810000380026C9000C0422050CFFFFFFFFFF96").unwrap();
+
+    assert_eq!(notify.di_statuses(), [true, false, true, false]);
+    assert_eq!(notify.di_changes(), [false, false, true, true]);
+    assert_eq!(notify.di_status_n(0), Some(true));
+    assert_eq!(notify.di_status_n(1), Some(false));
+    assert_eq!(notify.di_status_n(2), Some(true));
+    assert_eq!(notify.di_status_n(3), Some(false));
+    assert_eq!(notify.di_status_n(4), None);
+}
 
-    assert_eq!(Err(ValidateError::InvalidProtocolVersion(0x00)), notify.validate());
+#[test]
+fn test_ad_voltage_at_channel() {
+    // AD1 = 0x10, AD2 = 0x20, AD3 = 0x30, AD4 = 0xFF, fix = 0b11_10_01_00
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FF302010E4FF").unwrap();
+
+    assert_eq!(notify.ad_voltage_at_channel(1), Some(notify.ad1_voltage_millis()));
+    assert_eq!(notify.ad_voltage_at_channel(2), Some(notify.ad2_voltage_millis()));
+    assert_eq!(notify.ad_voltage_at_channel(3), Some(notify.ad3_voltage_millis()));
+    assert_eq!(notify.ad_voltage_at_channel(4), None);
+    assert_eq!(notify.ad_voltage_at_channel(1), Some((0x10 * 4) * 4));
+    assert_eq!(notify.ad_voltage_at_channel(2), Some((0x20 * 4 + 1) * 4));
+    assert_eq!(notify.ad_voltage_at_channel(3), Some((0x30 * 4 + 2) * 4));
+
+    assert_eq!(notify.ad_fixes(), [0, 1, 2, 3]);
+    assert!(!notify.ad_is_saturated(1));
+    assert!(!notify.ad_is_saturated(4));
+    assert_eq!(
+        notify.ad_voltages_millis(),
+        [(0x10 * 4) * 4, (0x20 * 4 + 1) * 4, (0x30 * 4 + 2) * 4, (0xFF * 4 + 3) * 4],
+    );
+
+    // AD1 = 0x7C, AD2 = 0x7B, AD3 = 0x7D, AD4 = 0xFF, fix = 0
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FF7D7B7C002F").unwrap();
+
+    assert_eq!(notify.ad_voltage_at_channel(1), Some(1984));
+    assert!(notify.ad_is_saturated(1));
+    assert!(!notify.ad_is_saturated(2));
+    assert!(notify.ad_is_saturated(3));
+    assert!(!notify.ad_is_saturated(4));
+    assert!(!notify.ad_is_saturated(5));
+    assert!(!notify.ad_is_saturated(usize::MAX));
+}
+
+#[test]
+fn test_bytes_roundtrip() {
+    let original =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    let notify = StatusNotify::from_bytes(original.into_bytes());
+
+    assert_eq!(notify.source_device_id(), original.source_device_id());
+    assert_eq!(notify.command(), original.command());
+    assert_eq!(notify.packet_id(), original.packet_id());
+    assert_eq!(notify.protocol_version(), original.protocol_version());
+    assert_eq!(notify.lqi(), original.lqi());
+    assert_eq!(notify.hardware_id(), original.hardware_id());
+    assert_eq!(notify.dest_device_id(), original.dest_device_id());
+    assert_eq!(notify.timestamp(), original.timestamp());
+    assert_eq!(notify.relay_count(), original.relay_count());
+    assert_eq!(notify.power_voltage_millis(), original.power_voltage_millis());
+    assert_eq!(notify.di_status(), original.di_status());
+    assert_eq!(notify.di_changed(), original.di_changed());
+    assert_eq!(notify.ad_value(), original.ad_value());
+    assert_eq!(notify.ad_fix(), original.ad_fix());
+    assert_eq!(notify.checksum(), original.checksum());
+    assert_eq!(notify.as_bytes(), original.as_bytes());
+}
+
+#[test]
+fn test_lqi_percent() {
+    assert_eq!(sample_with(4, 0).lqi_percent(), 0);
+    assert_eq!(sample_with(4, 255).lqi_percent(), 100);
+    assert_eq!(sample_with(4, 128).lqi_percent(), 50);
+}
+
+#[test]
+fn test_di_diff() {
+    let prev =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    // DI2 set, di_changed left at 0
+    let next =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220200FFFFFFFFFFA5").unwrap();
+
+    assert_eq!(next.di_diff(&prev), [false, true, false, false]);
+    assert_eq!(prev.di_diff(&next), [false, true, false, false]);
+    assert_eq!(next.di_diff(&next), [false; 4]);
+    assert_eq!(next.di_changes(), [false; 4]);
+}
+
+#[test]
+fn test_source_hardware_id_matches_prefix() {
+    // hardware_id = 0x81000038
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
 
+    assert!(notify.source_hardware_id_matches_prefix(0x00000000, 0));
+    assert!(notify.source_hardware_id_matches_prefix(0x80000000, 1));
+    assert!(notify.source_hardware_id_matches_prefix(0x81000000, 24));
+    assert!(!notify.source_hardware_id_matches_prefix(0x81000000, 32));
+    assert!(notify.source_hardware_id_matches_prefix(0x81000038, 32));
+    assert!(notify.source_hardware_id_matches_prefix(0x81000038, 40));
+    assert!(!notify.source_hardware_id_matches_prefix(0x01000000, 1));
+}
 
-    // Invalid Command
+#[test]
+fn test_validate_no_relay() {
     let notify =
-        StatusNotify::decode_str(":7880150175810000380026C9000C04220000FFFFFFFFFFA8").unwrap();
+        StatusNotify::decode_str(":7881150175810000380026C9020C04220000FFFFFFFFFFA5").unwrap();
+
+    assert_eq!(notify.validate(), Ok(()));
+    assert_eq!(notify.validate_no_relay(), Err(2));
+}
+
+/// The sample frame with byte `n` set to `value`. The checksum is left as is.
+#[cfg(test)]
+fn sample_with(n: usize, value: u8) -> StatusNotify {
+    let mut buf = StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7")
+        .unwrap()
+        .into_bytes();
+
+    buf[n] = value;
+    StatusNotify::from_bytes(buf)
+}
+
+#[test]
+fn test_source_device_id_parity() {
+    for (id, even) in [(0x00, true), (0x01, false), (0x64, true), (0xFF, false)] {
+        assert_eq!(sample_with(0, id).source_device_id_parity(), even);
+    }
+}
 
-    assert_eq!(Err(ValidateError::InvalidCommand(0x80)), notify.validate());
+#[test]
+fn test_source_device_id_to_index() {
+    let index_of = |id| sample_with(0, id).source_device_id_to_index();
+
+    assert_eq!(index_of(0x00), None);
+    assert_eq!(index_of(0x01), Some(0));
+    assert_eq!(index_of(0x64), Some(99));
+    assert_eq!(index_of(0x65), None);
+    assert_eq!(index_of(0xFF), None);
+}
 
+#[test]
+fn test_validate_packet_id_sequence() {
+    let (prev, next) = (sample_with(2, 0xFE), sample_with(2, 0x01));
+    assert_eq!(next.validate_packet_id_sequence(prev.packet_id(), 3), Ok(()));
+    assert_eq!(
+        next.validate_packet_id_sequence(prev.packet_id(), 2),
+        Err(PacketIdError::GapTooLarge { expected: 0xFF, got: 0x01, gap: 3 }),
+    );
+
+    assert_eq!(prev.validate_packet_id_sequence(prev.packet_id(), 0), Ok(()));
+}
 
-    // Invalid Realy Count
+#[test]
+fn test_validate_all_reserved_bits() {
     let notify =
-        StatusNotify::decode_str(":7881150175810000380026C9FF0C04220000FFFFFFFFFFA8").unwrap();
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220F0FFFFFFFFFFF89").unwrap();
+    assert_eq!(notify.validate_all_reserved_bits(), Ok(()));
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04221000FFFFFFFFFF97").unwrap();
+    assert_eq!(
+        notify.validate_all_reserved_bits(),
+        Err(ReservedBitError {
+            field: "di_status",
+            byte: 16,
+            expected_mask: 0x0F,
+            actual: 0x10,
+        }),
+    );
+
+    let notify =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220080FFFFFFFFFF27").unwrap();
+    assert_eq!(
+        notify.validate_all_reserved_bits().unwrap_err().field,
+        "di_changed",
+    );
+}
+
+#[test]
+fn test_lqi_stability_over() {
+    let window = [2, 4, 4, 4, 5, 5, 7, 9].map(|lqi| sample_with(4, lqi));
+
+    // population deviation is 2.0, sample deviation is sqrt(32 / 7)
+    assert!((StatusNotify::lqi_stability_over(&window) - 2.138_09).abs() < 1e-4);
+    assert_eq!(StatusNotify::lqi_stability_over(&window[..1]), 0.0);
+    assert_eq!(StatusNotify::lqi_stability_over(&[]), 0.0);
+}
+
+#[test]
+fn test_from_wireshark_hex() {
+    let expected =
+        StatusNotify::decode_str(":7881150175810000380026C9000C04220000FFFFFFFFFFA7").unwrap();
+
+    assert_eq!(
+        StatusNotify::from_wireshark_hex(
+            "78 81 15 01 75 81 00 00 38 00 26 C9 00 0C 04 22 00 00 FF FF FF FF FF A7"
+        ),
+        Ok(expected),
+    );
+    assert_eq!(
+        StatusNotify::from_wireshark_hex(
+            "78 81 15 01 75 81 00 00\n38 00 26 c9 00 0c 04 22\n00 00 ff ff ff ff ff a7\n"
+        ),
+        Ok(expected),
+    );
+    assert_eq!(
+        StatusNotify::from_wireshark_hex("78 81 15"),
+        Err(DecodeError::UnexpectedEof {
+            expected: PACKET_ASCII_LENGTH,
+            actual: 7,
+        }),
+    );
+    assert_eq!(
+        StatusNotify::from_wireshark_hex(
+            "78 81 15 01 75 81 00 00 38 00 26 C9 00 0C 04 22 00 00 FF FF FF FF FF A7 00"
+        ),
+        Err(DecodeError::InvalidLength {
+            expected: PACKET_ASCII_LENGTH,
+            actual: PACKET_ASCII_LENGTH + 2,
+        }),
+    );
+    assert_eq!(
+        StatusNotify::from_wireshark_hex(
+            "78 81 15 01 75 81 00 00 38 00 26 C9 00 0C 04 22 00 00 FF FF FF FF FF AG"
+        ),
+        Err(DecodeError::InvalidCharacter {
+            byte: b'G',
+            index: 48,
+        }),
+    );
+}
+
+#[test]
+fn test_packet_id_u16() {
+    let mut seq = 0;
+
+    for (id, expected) in [(0xFE, 0x00FE), (0xFE, 0x00FE), (0x01, 0x0101), (0x80, 0x0180), (0x00, 0x0200)] {
+        seq = sample_with(2, id).packet_id_u16(seq);
+        assert_eq!(seq, expected);
+    }
 
-    assert_eq!(Err(ValidateError::InvalidRelayCount(0xFF)), notify.validate());
+    assert_eq!(sample_with(2, 0x00).packet_id_u16(0xFFFF), 0x0000);
 }
@@ -0,0 +1,17 @@
+use test_case::test_case;
+use twelite_serial::{StatusNotify, ValidateError};
+
+#[test_case(":7881150175810000380026C9000C04220000FFFFFFFFFFA7", 0x78, 0x75, 0x00, Ok(()) ; "valid")]
+#[test_case(":7881150175810000380026C9000C04220100FFFFFFFFFFA6", 0x78, 0x75, 0x01, Ok(()) ; "di1")]
+#[test_case(":7881150175810000380026C9000C04220000FFFFFFFFFFFF", 0x78, 0x75, 0x00, Err(ValidateError::InvalidChecksum(0xFFu8 - 0xA7)) ; "invalid checksum")]
+#[test_case(":7881150075810000380026C9000C04220000FFFFFFFFFFA8", 0x78, 0x75, 0x00, Err(ValidateError::InvalidProtocolVersion(0x00)) ; "invalid protocol version")]
+#[test_case(":7880150175810000380026C9000C04220000FFFFFFFFFFA8", 0x78, 0x75, 0x00, Err(ValidateError::InvalidCommand(0x80)) ; "invalid command")]
+#[test_case(":7881150175810000380026C9FF0C04220000FFFFFFFFFFA8", 0x78, 0x75, 0x00, Err(ValidateError::InvalidRelayCount(0xFF)) ; "invalid relay count")]
+fn test_vector(hex: &str, source_device_id: u8, lqi: u8, di_status: u8, validate: Result<(), ValidateError>) {
+    let notify = StatusNotify::decode_str(hex).unwrap();
+
+    assert_eq!(notify.source_device_id(), source_device_id);
+    assert_eq!(notify.lqi(), lqi);
+    assert_eq!(notify.di_status(), di_status);
+    assert_eq!(notify.validate(), validate);
+}